@@ -0,0 +1,65 @@
+use core::alloc::Layout;
+use core::ptr::NonNull;
+
+#[cfg(feature = "allocator_api")]
+pub use alloc::alloc::Global;
+#[cfg(feature = "allocator_api")]
+pub use core::alloc::Allocator;
+
+#[cfg(not(feature = "allocator_api"))]
+pub use stable::{Allocator, Global};
+
+// Without the nightly `allocator_api` feature the only allocator is the global one. The trait is
+// sealed, so enabling the feature and replacing it with `core::alloc::Allocator` only adds
+// implementations.
+#[cfg(not(feature = "allocator_api"))]
+mod stable {
+    use core::alloc::Layout;
+    use core::ptr::NonNull;
+
+    pub trait Sealed {
+        fn allocate(&self, layout: Layout) -> Option<NonNull<u8>>;
+
+        // `ptr` must have been returned by `allocate(layout)`
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+    }
+
+    /// The allocator of the array backing a CircularBuffer.
+    ///
+    /// On stable only `Global` implements it. With the nightly `allocator_api` feature it is
+    /// `core::alloc::Allocator`, and a CircularBuffer can be created with any allocator with
+    /// `CircularBuffer::new_in`.
+    pub trait Allocator: Sealed {}
+
+    /// The global memory allocator, the one used by `Vec` and `Box`.
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct Global;
+
+    impl Sealed for Global {
+        fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+            NonNull::new(unsafe { alloc::alloc::alloc(layout) })
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            alloc::alloc::dealloc(ptr.as_ptr(), layout);
+        }
+    }
+
+    impl Allocator for Global {}
+}
+
+// `layout` must not be zero sized
+pub(crate) fn allocate<A: Allocator>(alloc: &A, layout: Layout) -> Option<NonNull<u8>> {
+    #[cfg(feature = "allocator_api")]
+    return alloc.allocate(layout).ok().map(NonNull::cast);
+    #[cfg(not(feature = "allocator_api"))]
+    return stable::Sealed::allocate(alloc, layout);
+}
+
+// `ptr` must have been returned by `allocate(alloc, layout)`
+pub(crate) unsafe fn deallocate<A: Allocator>(alloc: &A, ptr: NonNull<u8>, layout: Layout) {
+    #[cfg(feature = "allocator_api")]
+    alloc.deallocate(ptr, layout);
+    #[cfg(not(feature = "allocator_api"))]
+    stable::Sealed::deallocate(alloc, ptr, layout);
+}
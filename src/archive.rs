@@ -0,0 +1,130 @@
+use rkyv::bytecheck::{CheckBytes, Verify};
+use rkyv::munge::munge;
+use rkyv::rancor::{fail, Fallible, Source};
+use rkyv::ser::{Allocator as ArenaAllocator, Writer};
+use rkyv::vec::{ArchivedVec, VecResolver};
+use rkyv::{Archive, Archived, Deserialize, Place, Portable, Serialize};
+
+use crate::{Allocator, CapacityError, CircularBuffer};
+
+/// A CircularBuffer archived with `rkyv`, its elements can be read in place from the archive
+/// bytes.
+///
+/// It holds the capacity and the elements, from the oldest, in a single contiguous slice. The
+/// policy and the growth limit are not archived. Use `rkyv::access` to read it without copying
+/// the elements, or `rkyv::deserialize` to get back a CircularBuffer.
+///
+/// ```
+/// use rbl_circular_buffer::*;
+/// use rkyv::rancor::Error;
+///
+/// let mut buffer = CircularBuffer::new(3);
+/// buffer.extend_from_slice(&[1u32, 2, 3, 4]);
+/// let bytes = rkyv::to_bytes::<Error>(&buffer).unwrap();
+///
+/// let archived = rkyv::access::<ArchivedCircularBuffer<rkyv::Archived<u32>>, Error>(&bytes).unwrap();
+/// assert_eq!(3, archived.capacity());
+/// assert_eq!(&[2, 3, 4], archived.as_slice());
+///
+/// let back: CircularBuffer<u32> = rkyv::deserialize::<_, Error>(archived).unwrap();
+/// assert_eq!("CircularBuffer(2, 3, 4)", format!("{}", back));
+/// ```
+#[derive(Portable, CheckBytes)]
+#[bytecheck(crate = rkyv::bytecheck, verify)]
+#[repr(C)]
+pub struct ArchivedCircularBuffer<T> {
+    capacity: Archived<usize>,
+    elements: ArchivedVec<T>,
+}
+
+impl<T> ArchivedCircularBuffer<T> {
+    /// Returns the number of elements the archived CircularBuffer could hold.
+    pub fn capacity(&self) -> usize {
+        self.capacity.to_native() as usize
+    }
+
+    /// Returns the amount of elements in the archived CircularBuffer.
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Returns `true` if there are no elements in the archived CircularBuffer.
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Returns the elements, from the oldest to the newest, without copying them.
+    pub fn as_slice(&self) -> &[T] {
+        self.elements.as_slice()
+    }
+
+    /// Returns an iterator over references to the elements, from the oldest to the newest.
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.as_slice().iter()
+    }
+}
+
+// the archive is checked to hold no more elements than its capacity, so deserializing a checked
+// archive never fails for its size
+unsafe impl<T, C> Verify<C> for ArchivedCircularBuffer<T>
+where
+    C: Fallible + ?Sized,
+    C::Error: Source,
+{
+    fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+        if self.len() > self.capacity() {
+            fail!(CapacityError::new(self.capacity(), self.len()));
+        }
+        Ok(())
+    }
+}
+
+impl<T: Archive, P, A: Allocator> Archive for CircularBuffer<T, P, A> {
+    type Archived = ArchivedCircularBuffer<T::Archived>;
+    type Resolver = VecResolver;
+
+    fn resolve(&self, resolver: VecResolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedCircularBuffer { capacity, elements } = out);
+        self.capacity().resolve((), capacity);
+        ArchivedVec::resolve_from_len(self.len(), resolver, elements);
+    }
+}
+
+/// Archive the elements from the oldest, the two halves of the CircularBuffer are written as a
+/// single slice.
+impl<T, P, A, S> Serialize<S> for CircularBuffer<T, P, A>
+where
+    T: Serialize<S>,
+    A: Allocator,
+    S: Fallible + ArenaAllocator + Writer + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<VecResolver, S::Error> {
+        ArchivedVec::<T::Archived>::serialize_from_iter::<T, _, _>(self.iter(), serializer)
+    }
+}
+
+/// Deserialize the archived elements into a new CircularBuffer of the archived capacity.
+///
+/// It fails if there are more elements than the capacity, which only an unchecked archive can
+/// hold. As with serde, an array of more than 1 MiB for a capacity much larger than the elements
+/// is not allocated up front: the CircularBuffer starts with a smaller array, that grows as
+/// elements are pushed, and still reports and archives the archived capacity.
+impl<T, D> Deserialize<CircularBuffer<T>, D> for ArchivedCircularBuffer<T::Archived>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<CircularBuffer<T>, D::Error> {
+        if self.len() > self.capacity() {
+            fail!(CapacityError::new(self.capacity(), self.len()));
+        }
+        let mut buffer = CircularBuffer::try_for_deserialize(self.capacity(), self.len())
+            .map_err(D::Error::new)?;
+        for element in self.iter() {
+            buffer.push(element.deserialize(deserializer)?);
+        }
+        Ok(buffer)
+    }
+}
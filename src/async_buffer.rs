@@ -0,0 +1,324 @@
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+use futures_core::Stream;
+use futures_sink::Sink;
+
+use crate::CircularBuffer;
+
+/// A CircularBuffer shared between tasks, that yields its elements as a `Stream` and accepts
+/// them as a `Sink`.
+///
+/// Every clone is a handle to the same CircularBuffer. `push` never waits and, like
+/// `CircularBuffer::push`, overwrites the oldest element when the buffer is full. Polled as a
+/// `Stream` it pops the elements from the oldest, and when it is empty it waits for the next
+/// push. The stream ends once the buffer is closed and empty.
+///
+/// As a `Sink`, a buffer created with `new` is always ready and overwrites the oldest element,
+/// one created with `no_overwrite` is ready only when it has free space. Several clones may send
+/// at once: an element that finds the buffer full, because another clone filled it after
+/// `poll_ready`, is held by its handle and pushed by the next `poll_ready`, `poll_flush` or
+/// `poll_close`, it never overwrites.
+///
+/// ```
+/// use futures_core::Stream;
+/// use rbl_circular_buffer::*;
+/// use std::future::poll_fn;
+/// use std::pin::Pin;
+///
+/// let samples = AsyncCircularBuffer::new(8);
+/// let mut stream = samples.clone();
+/// samples.push(1);
+/// samples.push(2);
+/// samples.close();
+///
+/// let mut sum = 0;
+/// futures_executor::block_on(async {
+///     while let Some(sample) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+///         sum += sample;
+///     }
+/// });
+/// assert_eq!(3, sum);
+/// ```
+pub struct AsyncCircularBuffer<T> {
+    shared: Arc<Mutex<State<T>>>,
+    // the element sent by this handle as a `Sink` that found no free space
+    pending: Option<T>,
+}
+
+// `pending` is never pinned, it is only moved in and out of the handle.
+impl<T> Unpin for AsyncCircularBuffer<T> {}
+
+struct State<T> {
+    buffer: CircularBuffer<T>,
+    // the tasks waiting for an element
+    readers: Vec<Waker>,
+    // the tasks waiting for free space, only without overwrite
+    writers: Vec<Waker>,
+    overwrite: bool,
+    closed: bool,
+}
+
+impl<T> State<T> {
+    // the wakers are woken once the lock is released
+    fn push(&mut self, value: T) -> (usize, Vec<Waker>) {
+        let empty_spots = self.buffer.push(value);
+        (empty_spots, core::mem::take(&mut self.readers))
+    }
+
+    // without overwrite, a `Sink` sends only when there is free space
+    fn accepts(&self) -> bool {
+        self.overwrite || self.buffer.capacity() == 0 || !self.buffer.is_full()
+    }
+
+    // the wakers are woken once the lock is released
+    fn pop(&mut self) -> (Option<T>, Vec<Waker>) {
+        let element = self.buffer.next();
+        if element.is_none() {
+            return (None, Vec::new());
+        }
+        (element, core::mem::take(&mut self.writers))
+    }
+}
+
+fn wake_all(wakers: Vec<Waker>) {
+    for waker in wakers {
+        waker.wake();
+    }
+}
+
+// registers the task of `cx` only once, a task polled again keeps the same waker
+fn register(wakers: &mut Vec<Waker>, cx: &Context<'_>) {
+    if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+        wakers.push(cx.waker().clone());
+    }
+}
+
+impl<T> AsyncCircularBuffer<T> {
+    /// Create a new AsyncCircularBuffer of size `size`.
+    pub fn new(size: usize) -> Self {
+        AsyncCircularBuffer::from(CircularBuffer::new(size))
+    }
+
+    /// Create a new AsyncCircularBuffer of size `size` that, as a `Sink`, waits for free space
+    /// instead of overwriting the oldest element.
+    ///
+    /// A zero sized buffer is always ready, and drops every element sent.
+    ///
+    /// ```
+    /// use futures_sink::Sink;
+    /// use rbl_circular_buffer::*;
+    /// use std::pin::Pin;
+    /// use std::task::{Context, Poll};
+    ///
+    /// let mut sink = AsyncCircularBuffer::no_overwrite(1);
+    /// let mut cx = Context::from_waker(std::task::Waker::noop());
+    /// assert!(Pin::new(&mut sink).poll_ready(&mut cx).is_ready());
+    /// Pin::new(&mut sink).start_send(1).unwrap();
+    /// assert!(Pin::new(&mut sink).poll_ready(&mut cx).is_pending());
+    /// assert_eq!(Some(1), sink.try_pop());
+    /// assert!(Pin::new(&mut sink).poll_ready(&mut cx).is_ready());
+    /// ```
+    pub fn no_overwrite(size: usize) -> Self {
+        let buffer = AsyncCircularBuffer::new(size);
+        buffer.lock().overwrite = false;
+        buffer
+    }
+
+    // the CircularBuffer is consistent after every operation, even if a thread panicked while
+    // holding the lock
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.shared.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Push a new element, without waiting, and wake up the tasks waiting for it.
+    ///
+    /// It follows the same semantics of `CircularBuffer::push`: if the buffer is full the oldest
+    /// element is overwritten. It returns the empty spots left.
+    pub fn push(&self, value: T) -> usize {
+        let (empty_spots, readers) = self.lock().push(value);
+        wake_all(readers);
+        empty_spots
+    }
+
+    /// Remove the oldest element and return it, or `None` if the buffer is empty, without
+    /// waiting.
+    pub fn try_pop(&self) -> Option<T> {
+        let (element, writers) = self.lock().pop();
+        wake_all(writers);
+        element
+    }
+
+    /// Remove the oldest element and return it, waiting for one to be pushed if the buffer is
+    /// empty.
+    ///
+    /// The task is woken up by the next push, it never polls. It keeps waiting after the buffer
+    /// is closed.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let queue = AsyncCircularBuffer::new(4);
+    /// let producer = {
+    ///     let queue = queue.clone();
+    ///     std::thread::spawn(move || queue.push(42))
+    /// };
+    /// assert_eq!(42, futures_executor::block_on(queue.pop()));
+    /// producer.join().unwrap();
+    /// ```
+    pub async fn pop(&self) -> T {
+        let element = core::future::poll_fn(|cx| self.poll_pop(cx, false)).await;
+        // without `stop_when_closed` it never returns `None`
+        element.unwrap()
+    }
+
+    // pops the oldest element, or registers the task to be woken up by the next push
+    fn poll_pop(&self, cx: &Context<'_>, stop_when_closed: bool) -> Poll<Option<T>> {
+        let mut state = self.lock();
+        let (element, writers) = state.pop();
+        if element.is_some() {
+            drop(state);
+            wake_all(writers);
+            return Poll::Ready(element);
+        }
+        if stop_when_closed && state.closed {
+            return Poll::Ready(None);
+        }
+        register(&mut state.readers, cx);
+        Poll::Pending
+    }
+
+    /// End the stream once the elements left are popped, the tasks waiting are woken up.
+    ///
+    /// Elements pushed after closing are still yielded.
+    pub fn close(&self) {
+        let mut state = self.lock();
+        state.closed = true;
+        let readers = core::mem::take(&mut state.readers);
+        drop(state);
+        wake_all(readers);
+    }
+
+    // pushes the element held by the `Sink`, or registers the task to be woken up by the next pop
+    fn poll_pending(&mut self, cx: &Context<'_>) -> Poll<()> {
+        let item = match self.pending.take() {
+            Some(item) => item,
+            None => return Poll::Ready(()),
+        };
+        let mut state = self.lock();
+        if state.accepts() {
+            let (_, readers) = state.push(item);
+            drop(state);
+            wake_all(readers);
+            return Poll::Ready(());
+        }
+        register(&mut state.writers, cx);
+        drop(state);
+        self.pending = Some(item);
+        Poll::Pending
+    }
+
+    /// Returns the amount of elements in the buffer, it may change as soon as it is returned.
+    pub fn len(&self) -> usize {
+        self.lock().buffer.len()
+    }
+
+    /// Returns `true` if there are no elements in the buffer.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements the buffer can hold.
+    pub fn capacity(&self) -> usize {
+        self.lock().buffer.capacity()
+    }
+}
+
+/// Another handle to the same CircularBuffer, without the element this handle holds as a `Sink`.
+impl<T> Clone for AsyncCircularBuffer<T> {
+    fn clone(&self) -> Self {
+        AsyncCircularBuffer {
+            shared: self.shared.clone(),
+            pending: None,
+        }
+    }
+}
+
+/// Share an existing CircularBuffer, with the elements it already holds.
+impl<T> From<CircularBuffer<T>> for AsyncCircularBuffer<T> {
+    fn from(buffer: CircularBuffer<T>) -> Self {
+        AsyncCircularBuffer {
+            shared: Arc::new(Mutex::new(State {
+                buffer,
+                readers: Vec::new(),
+                writers: Vec::new(),
+                overwrite: true,
+                closed: false,
+            })),
+            pending: None,
+        }
+    }
+}
+
+/// Pop the elements, waiting for a push when the buffer is empty.
+impl<T> Stream for AsyncCircularBuffer<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.poll_pop(cx, true)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), None)
+    }
+}
+
+/// Push the elements sent, `poll_close` closes the buffer.
+///
+/// Without overwrite, the free space is checked again under the lock when the element is sent: if
+/// another handle took it, the element is held until there is room, and dropped with the handle
+/// if it is never flushed.
+impl<T> Sink<T> for AsyncCircularBuffer<T> {
+    type Error = core::convert::Infallible;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        if this.poll_pending(cx).is_pending() {
+            return Poll::Pending;
+        }
+        let mut state = this.lock();
+        if state.accepts() {
+            return Poll::Ready(Ok(()));
+        }
+        register(&mut state.writers, cx);
+        Poll::Pending
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        let this = self.get_mut();
+        let mut state = this.lock();
+        if !state.accepts() {
+            drop(state);
+            this.pending = Some(item);
+            return Ok(());
+        }
+        let (_, readers) = state.push(item);
+        drop(state);
+        wake_all(readers);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().poll_pending(cx).map(Ok)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        if this.poll_pending(cx).is_pending() {
+            return Poll::Pending;
+        }
+        this.close();
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,150 @@
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use crate::CircularBuffer;
+
+/// A CircularBuffer shared between threads, where readers can wait for elements and writers for
+/// free space.
+///
+/// It is a `Mutex` and two `Condvar`s around a CircularBuffer: `push` never waits and, like
+/// `CircularBuffer::push`, overwrites the oldest element when the buffer is full. Writers that
+/// must not lose elements call `wait_until_free` before pushing.
+///
+/// ```
+/// use rbl_circular_buffer::*;
+/// use std::sync::Arc;
+///
+/// let queue = Arc::new(BlockingCircularBuffer::new(4));
+/// let producer = {
+///     let queue = queue.clone();
+///     std::thread::spawn(move || {
+///         for i in 0..10 {
+///             queue.wait_until_free(1);
+///             queue.push(i);
+///         }
+///     })
+/// };
+/// let received: Vec<u32> = (0..10).map(|_| queue.pop_wait()).collect();
+/// producer.join().unwrap();
+/// assert_eq!((0..10).collect::<Vec<_>>(), received);
+/// ```
+pub struct BlockingCircularBuffer<T> {
+    buffer: Mutex<CircularBuffer<T>>,
+    // notified after every push
+    not_empty: Condvar,
+    // notified after every pop
+    popped: Condvar,
+}
+
+impl<T> BlockingCircularBuffer<T> {
+    /// Create a new BlockingCircularBuffer of size `size`.
+    pub fn new(size: usize) -> Self {
+        BlockingCircularBuffer::from(CircularBuffer::new(size))
+    }
+
+    // the CircularBuffer is consistent after every operation, even if a thread panicked while
+    // holding the lock
+    fn lock(&self) -> MutexGuard<'_, CircularBuffer<T>> {
+        self.buffer.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Push a new element, without waiting, and wake up a thread waiting for it.
+    ///
+    /// It follows the same semantics of `CircularBuffer::push`: if the buffer is full the oldest
+    /// element is overwritten. It returns the empty spots left.
+    pub fn push(&self, value: T) -> usize {
+        let empty_spots = self.lock().push(value);
+        self.not_empty.notify_one();
+        empty_spots
+    }
+
+    /// Remove the oldest element and return it, or `None` if the buffer is empty, without
+    /// waiting.
+    pub fn try_pop(&self) -> Option<T> {
+        let element = self.lock().next();
+        if element.is_some() {
+            self.popped.notify_all();
+        }
+        element
+    }
+
+    /// Remove the oldest element and return it, waiting for one to be pushed if the buffer is
+    /// empty.
+    pub fn pop_wait(&self) -> T {
+        let mut buffer = self
+            .not_empty
+            .wait_while(self.lock(), |buffer| buffer.is_empty())
+            .unwrap_or_else(PoisonError::into_inner);
+        let element = buffer.next().unwrap();
+        drop(buffer);
+        self.popped.notify_all();
+        element
+    }
+
+    /// Remove the oldest element and return it, waiting at most `timeout` for one to be pushed.
+    ///
+    /// It returns `None` if the buffer is still empty after `timeout`.
+    pub fn pop_timeout(&self, timeout: Duration) -> Option<T> {
+        let (mut buffer, _) = self
+            .not_empty
+            .wait_timeout_while(self.lock(), timeout, |buffer| buffer.is_empty())
+            .unwrap_or_else(PoisonError::into_inner);
+        let element = buffer.next();
+        drop(buffer);
+        if element.is_some() {
+            self.popped.notify_all();
+        }
+        element
+    }
+
+    /// Wait until at least `n` elements can be pushed without overwriting any element.
+    ///
+    /// Another writer may fill the space before the caller pushes, use a single writer to push
+    /// without losing elements.
+    ///
+    /// Panics if `n` is greater than the capacity, the space would never be free.
+    pub fn wait_until_free(&self, n: usize) {
+        let buffer = self.lock();
+        assert!(
+            n <= buffer.capacity(),
+            "waiting for {} free slots in a buffer of {}",
+            n,
+            buffer.capacity()
+        );
+        let _buffer = self
+            .popped
+            .wait_while(buffer, |buffer| buffer.free_space() < n)
+            .unwrap_or_else(PoisonError::into_inner);
+    }
+
+    /// Returns the amount of elements in the buffer, it may change as soon as it is returned.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` if there are no elements in the buffer.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements the buffer can hold.
+    pub fn capacity(&self) -> usize {
+        self.lock().capacity()
+    }
+
+    /// Consume the BlockingCircularBuffer, returning the CircularBuffer inside.
+    pub fn into_inner(self) -> CircularBuffer<T> {
+        self.buffer.into_inner().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Share an existing CircularBuffer, with the elements it already holds.
+impl<T> From<CircularBuffer<T>> for BlockingCircularBuffer<T> {
+    fn from(buffer: CircularBuffer<T>) -> Self {
+        BlockingCircularBuffer {
+            buffer: Mutex::new(buffer),
+            not_empty: Condvar::new(),
+            popped: Condvar::new(),
+        }
+    }
+}
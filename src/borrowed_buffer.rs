@@ -0,0 +1,164 @@
+use core::mem::MaybeUninit;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::ring::{fmt_elements, Ring};
+use crate::{Iter, IterRev};
+
+/// A circular buffer over storage provided by the caller, it never allocates.
+///
+/// The storage can live anywhere: in a `static`, in an array on the stack or in a memory region
+/// chosen for DMA. Its size is the capacity of the buffer. Like a `CircularBuffer` with the
+/// default `OldestFirst` policy, when it is full `push` overwrites the oldest element.
+///
+/// The elements still in the buffer are dropped with it, the storage is then free to be reused.
+///
+/// ```
+/// use core::mem::MaybeUninit;
+/// use rbl_circular_buffer::*;
+///
+/// let mut storage = [MaybeUninit::<u32>::uninit(); 3];
+/// let mut buffer = BorrowedCircularBuffer::from_storage(&mut storage);
+/// for i in 0..5 {
+///     buffer.push(i);
+/// }
+/// assert_eq!(3, buffer.capacity());
+/// assert_eq!("BorrowedCircularBuffer(2, 3, 4)", format!("{}", buffer));
+/// ```
+pub struct BorrowedCircularBuffer<'a, T> {
+    // only the slots between the reading and the writing pointer are initialized
+    buffer: &'a mut [MaybeUninit<T>],
+    ring: Ring,
+}
+
+impl<'a, T> BorrowedCircularBuffer<'a, T> {
+    /// Create a new, empty, BorrowedCircularBuffer using `storage` for its elements.
+    ///
+    /// Whatever `storage` contains is ignored and never dropped. An empty `storage` is valid, the
+    /// buffer then holds nothing and drops every element pushed.
+    pub fn from_storage(storage: &'a mut [MaybeUninit<T>]) -> Self {
+        BorrowedCircularBuffer {
+            buffer: storage,
+            ring: Ring::new(),
+        }
+    }
+
+    /// Returns the amount of elements in the BorrowedCircularBuffer in O(1)
+    pub fn len(&self) -> usize {
+        self.ring.len(self.buffer.len())
+    }
+
+    /// Returns `true` if there are no elements in the BorrowedCircularBuffer.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements the BorrowedCircularBuffer can hold, the length of its
+    /// storage.
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns `true` if the BorrowedCircularBuffer is full, the next `push` will overwrite the
+    /// oldest element.
+    pub fn is_full(&self) -> bool {
+        self.ring.is_full()
+    }
+
+    /// Returns the number of elements that can be pushed before the BorrowedCircularBuffer is
+    /// full.
+    pub fn free_space(&self) -> usize {
+        self.capacity() - self.len()
+    }
+
+    /// Push a new element into the BorrowedCircularBuffer in O(1), returns the empty spots left.
+    ///
+    /// If the BorrowedCircularBuffer is full, the oldest element is overwritten.
+    pub fn push(&mut self, value: T) -> usize {
+        self.ring.push(self.buffer, value)
+    }
+
+    /// Moves the oldest elements into `return_vector`, without allocating, it returns the number
+    /// of elements moved.
+    ///
+    /// It behaves like `CircularBuffer::fill`: only the spare capacity of the vector is filled.
+    #[cfg(feature = "alloc")]
+    pub fn fill(&mut self, return_vector: &mut Vec<T>) -> usize {
+        let mut i = 0;
+        while return_vector.capacity() - return_vector.len() > 0 {
+            match self.next() {
+                Some(element) => {
+                    return_vector.push(element);
+                    i += 1;
+                }
+                None => return i,
+            }
+        }
+        i
+    }
+
+    /// Moves the oldest elements into `out`, from its start, it returns the number of elements
+    /// moved.
+    ///
+    /// It behaves like `CircularBuffer::fill_slice`, and needs no allocator.
+    pub fn fill_slice(&mut self, out: &mut [T]) -> usize {
+        let mut i = 0;
+        for (slot, element) in out.iter_mut().zip(self.by_ref()) {
+            *slot = element;
+            i += 1;
+        }
+        i
+    }
+
+    /// Returns an iterator over references to the elements, from the oldest to the newest,
+    /// without consuming them.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.ring.iter(self.buffer)
+    }
+
+    /// Returns an iterator over references to the elements, from the newest to the oldest,
+    /// without consuming them.
+    pub fn iter_rev(&self) -> IterRev<'_, T> {
+        IterRev::new(self.iter())
+    }
+
+    /// Drops all the elements, leaving the BorrowedCircularBuffer empty.
+    pub fn clear(&mut self) {
+        self.ring.clear(self.buffer);
+    }
+}
+
+impl<'a, T> Drop for BorrowedCircularBuffer<'a, T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// Consume the elements, from the oldest to the newest.
+impl<'a, T> Iterator for BorrowedCircularBuffer<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.ring.pop(self.buffer)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
+
+impl<'a, 'b, T> IntoIterator for &'b BorrowedCircularBuffer<'a, T> {
+    type Item = &'b T;
+    type IntoIter = Iter<'b, T>;
+
+    fn into_iter(self) -> Iter<'b, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T: core::fmt::Display> core::fmt::Display for BorrowedCircularBuffer<'a, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fmt_elements(f, "BorrowedCircularBuffer", self.iter())
+    }
+}
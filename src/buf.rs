@@ -0,0 +1,73 @@
+use bytes::buf::UninitSlice;
+use bytes::{Buf, BufMut};
+
+use crate::{Allocator, CircularBuffer};
+
+/// Read the bytes in place, from the oldest: `chunk` returns the bytes up to the end of the
+/// array, `advance` removes them.
+impl<P, A: Allocator> Buf for CircularBuffer<u8, P, A> {
+    fn remaining(&self) -> usize {
+        self.len()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        let (older, _) = self.as_raw_slices();
+        older
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        assert!(
+            cnt <= self.len(),
+            "advance {} is greater than len {}",
+            cnt,
+            self.len()
+        );
+        self.advance_read(cnt);
+    }
+}
+
+/// Write the bytes in place, in the free slots: a BufMut never overwrites, whatever the policy,
+/// and `remaining_mut` is the free space.
+///
+/// ```
+/// use bytes::{Buf, BufMut};
+/// use rbl_circular_buffer::*;
+///
+/// let mut buffer = CircularBuffer::new(8);
+/// buffer.put_u32(0xCAFE_F00D);
+/// buffer.put_slice(b"ok");
+/// assert_eq!(0xCAFE_F00D, buffer.get_u32());
+/// assert_eq!(b"ok", buffer.chunk());
+/// ```
+unsafe impl<P, A: Allocator> BufMut for CircularBuffer<u8, P, A> {
+    // the free slots of the array, a growable CircularBuffer does not grow for `BufMut`
+    fn remaining_mut(&self) -> usize {
+        self.size - self.len()
+    }
+
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        assert!(
+            cnt <= self.remaining_mut(),
+            "advance {} is greater than the free space {}",
+            cnt,
+            self.remaining_mut()
+        );
+        let len = self.len();
+        if cnt > 0 {
+            self.set_len(len + cnt);
+        }
+    }
+
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        // the free slots from the writing pointer, up to the reading pointer or to the end of
+        // the array
+        let end = if self.full {
+            self.w
+        } else if self.w < self.r {
+            self.r
+        } else {
+            self.size
+        };
+        unsafe { UninitSlice::from_raw_parts_mut(self.as_mut_ptr().add(self.w), end - self.w) }
+    }
+}
@@ -0,0 +1,124 @@
+use core::ops::{Add, Sub};
+
+use crate::iter::{Iter, Pairs};
+use crate::CircularBuffer;
+
+/// A CircularBuffer that stores raw values and exposes their successive differences.
+///
+/// It is useful to convert monotonic counters into rates over the window.
+///
+/// ```
+/// use rbl_circular_buffer::*;
+///
+/// let mut requests = DeltaBuffer::new(4);
+/// for counter in [100, 110, 130, 135, 150] {
+///     requests.push(counter);
+/// }
+/// let rates: Vec<_> = requests.deltas().collect();
+/// assert_eq!(vec![20, 5, 15], rates);
+/// ```
+pub struct DeltaBuffer<T> {
+    buffer: CircularBuffer<T>,
+}
+
+impl<T> DeltaBuffer<T> {
+    /// Create a new DeltaBuffer that holds `size` raw values.
+    ///
+    /// A DeltaBuffer of size `n` yields at most `n - 1` differences.
+    pub fn new(size: usize) -> Self {
+        DeltaBuffer {
+            buffer: CircularBuffer::new(size),
+        }
+    }
+
+    /// Push a new raw value, with the same semantics of `CircularBuffer::push`.
+    pub fn push(&mut self, value: T) -> usize {
+        self.buffer.push(value)
+    }
+
+    /// The raw values.
+    pub fn buffer(&self) -> &CircularBuffer<T> {
+        &self.buffer
+    }
+
+    /// Returns the CircularBuffer with the raw values.
+    pub fn into_inner(self) -> CircularBuffer<T> {
+        self.buffer
+    }
+}
+
+impl<T: Copy + Sub<Output = T>> DeltaBuffer<T> {
+    /// Iterate over the differences between each raw value and the previous one.
+    pub fn deltas(&self) -> Deltas<'_, T> {
+        Deltas {
+            pairs: self.buffer.iter_pairs(),
+        }
+    }
+}
+
+impl<T: Copy + Add<Output = T>> DeltaBuffer<T> {
+    /// Iterate over the running sum of the raw values, the integral of the window.
+    pub fn cumsum(&self) -> CumSum<'_, T> {
+        CumSum {
+            iter: Iter::new(&self.buffer),
+            sum: None,
+        }
+    }
+}
+
+impl<T> From<CircularBuffer<T>> for DeltaBuffer<T> {
+    fn from(buffer: CircularBuffer<T>) -> Self {
+        DeltaBuffer { buffer }
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for DeltaBuffer<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(&self.buffer, f)
+    }
+}
+
+/// Iterator over the differences of a DeltaBuffer, created by `DeltaBuffer::deltas`.
+pub struct Deltas<'a, T> {
+    pairs: Pairs<'a, T>,
+}
+
+impl<'a, T: Copy + Sub<Output = T>> Iterator for Deltas<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.pairs.next().map(|(previous, current)| *current - *previous)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pairs.size_hint()
+    }
+}
+
+impl<'a, T: Copy + Sub<Output = T>> ExactSizeIterator for Deltas<'a, T> {}
+
+/// Iterator over the running sum of a DeltaBuffer, created by `DeltaBuffer::cumsum`.
+pub struct CumSum<'a, T> {
+    iter: Iter<'a, T>,
+    sum: Option<T>,
+}
+
+impl<'a, T: Copy + Add<Output = T>> Iterator for CumSum<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let value = *self.iter.next()?;
+        let sum = match self.sum {
+            Some(sum) => sum + value,
+            None => value,
+        };
+        self.sum = Some(sum);
+        Some(sum)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T: Copy + Add<Output = T>> ExactSizeIterator for CumSum<'a, T> {}
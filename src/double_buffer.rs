@@ -0,0 +1,227 @@
+use alloc::sync::Arc;
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::CircularBuffer;
+
+/// Two CircularBuffers of the same size, one written by the producer and one read by the consumer.
+///
+/// The producer pushes into the back buffer while the consumer drains the front buffer, then
+/// `swap()` exchanges them. The swap is O(1), no element is moved, and it happens in a single
+/// step so the consumer never sees a frame partially written.
+///
+/// The elements the consumer did not drain are dropped on swap, so the producer always starts
+/// from an empty buffer.
+///
+/// `swap` takes the DoubleBuffer mutably, so the producer and the consumer must be in the same
+/// place. To fill and drain it from two threads, `split` it.
+///
+/// ```
+/// use rbl_circular_buffer::*;
+///
+/// let mut frames = DoubleBuffer::new(4);
+/// frames.push(1);
+/// frames.push(2);
+/// assert_eq!(0, frames.front().len());
+///
+/// frames.swap();
+/// let frame: Vec<_> = frames.front_mut().collect();
+/// assert_eq!(vec![1, 2], frame);
+/// assert_eq!(0, frames.back().len());
+/// ```
+pub struct DoubleBuffer<T> {
+    front: CircularBuffer<T>,
+    back: CircularBuffer<T>,
+}
+
+impl<T> DoubleBuffer<T> {
+    /// Create a DoubleBuffer made of two CircularBuffer of size `size`.
+    pub fn new(size: usize) -> Self {
+        DoubleBuffer {
+            front: CircularBuffer::new(size),
+            back: CircularBuffer::new(size),
+        }
+    }
+
+    /// Push a new element into the back buffer, it follows the same semantics of
+    /// `CircularBuffer::push`.
+    pub fn push(&mut self, value: T) -> usize {
+        self.back.push(value)
+    }
+
+    /// Exchange the two buffers, the back buffer becomes the front one.
+    ///
+    /// The elements left in the front buffer are dropped.
+    pub fn swap(&mut self) {
+        self.front.clear();
+        core::mem::swap(&mut self.front, &mut self.back);
+    }
+
+    /// The buffer read by the consumer.
+    pub fn front(&self) -> &CircularBuffer<T> {
+        &self.front
+    }
+
+    /// The buffer read by the consumer, use it to drain the elements.
+    pub fn front_mut(&mut self) -> &mut CircularBuffer<T> {
+        &mut self.front
+    }
+
+    /// The buffer written by the producer.
+    pub fn back(&self) -> &CircularBuffer<T> {
+        &self.back
+    }
+
+    /// The buffer written by the producer.
+    pub fn back_mut(&mut self) -> &mut CircularBuffer<T> {
+        &mut self.back
+    }
+
+    /// Split the DoubleBuffer into a `BackBuffer`, filled by the producer, and a `FrontBuffer`,
+    /// drained by the consumer, that can be moved to two different threads.
+    ///
+    /// A third CircularBuffer of the same size is kept between the two, so neither side ever
+    /// waits for the other: `BackBuffer::publish` exchanges the back buffer with it, and
+    /// `FrontBuffer::swap` exchanges the front buffer with it if a frame was published. Each
+    /// exchange is a single atomic swap of an index, no element is moved.
+    ///
+    /// If the producer publishes a new frame before the consumer takes the previous one, the
+    /// previous frame is dropped: the consumer always gets the newest frame.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let (mut game, mut audio) = DoubleBuffer::new(64).split();
+    /// let producer = std::thread::spawn(move || {
+    ///     game.push(1);
+    ///     game.push(2);
+    ///     game.publish();
+    /// });
+    /// producer.join().unwrap();
+    ///
+    /// assert!(audio.swap());
+    /// let frame: Vec<_> = audio.buffer_mut().collect();
+    /// assert_eq!(vec![1, 2], frame);
+    /// assert!(!audio.swap());
+    /// ```
+    pub fn split(self) -> (BackBuffer<T>, FrontBuffer<T>) {
+        let size = self.front.capacity();
+        let shared = Arc::new(Shared {
+            rings: [
+                UnsafeCell::new(self.front),
+                UnsafeCell::new(self.back),
+                UnsafeCell::new(CircularBuffer::new(size)),
+            ],
+            middle: AtomicUsize::new(2),
+        });
+        (
+            BackBuffer {
+                shared: shared.clone(),
+                back: 1,
+            },
+            FrontBuffer { shared, front: 0 },
+        )
+    }
+}
+
+// set in `Shared::middle` when the ring in the middle holds a frame not taken yet
+const FRESH: usize = 0b100;
+
+// The three rings of a split DoubleBuffer.
+//
+// Each ring belongs to a single side at any time: the BackBuffer owns the ring at its `back`
+// index, the FrontBuffer the one at its `front` index, and `middle` is the index of the third
+// one. A side gives its ring away and takes the middle one with a single atomic swap.
+struct Shared<T> {
+    rings: [UnsafeCell<CircularBuffer<T>>; 3],
+    middle: AtomicUsize,
+}
+
+// the rings are moved between the two threads, never shared
+unsafe impl<T: Send> Send for Shared<T> {}
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+/// The half of a split DoubleBuffer filled by the producer.
+///
+/// It is created by `DoubleBuffer::split`.
+pub struct BackBuffer<T> {
+    shared: Arc<Shared<T>>,
+    back: usize,
+}
+
+/// The half of a split DoubleBuffer drained by the consumer.
+///
+/// It is created by `DoubleBuffer::split`.
+pub struct FrontBuffer<T> {
+    shared: Arc<Shared<T>>,
+    front: usize,
+}
+
+impl<T> BackBuffer<T> {
+    /// Push a new element into the back buffer, it follows the same semantics of
+    /// `CircularBuffer::push`.
+    pub fn push(&mut self, value: T) -> usize {
+        self.buffer_mut().push(value)
+    }
+
+    /// Hand the back buffer to the consumer, as the next frame, and start from an empty buffer.
+    ///
+    /// The elements of the buffer received in exchange, the ones the consumer did not take, are
+    /// dropped.
+    pub fn publish(&mut self) {
+        let middle = self.shared.middle.swap(self.back | FRESH, Ordering::AcqRel);
+        self.back = middle & !FRESH;
+        self.buffer_mut().clear();
+    }
+
+    /// The buffer written by the producer.
+    pub fn buffer(&self) -> &CircularBuffer<T> {
+        unsafe { &*self.shared.rings[self.back].get() }
+    }
+
+    /// The buffer written by the producer.
+    pub fn buffer_mut(&mut self) -> &mut CircularBuffer<T> {
+        unsafe { &mut *self.shared.rings[self.back].get() }
+    }
+}
+
+impl<T> FrontBuffer<T> {
+    /// Take the newest frame published by the producer, returns `false` if there is none and
+    /// the front buffer is left untouched.
+    ///
+    /// The elements left in the front buffer are dropped.
+    pub fn swap(&mut self) -> bool {
+        if self.shared.middle.load(Ordering::Acquire) & FRESH == 0 {
+            return false;
+        }
+        self.buffer_mut().clear();
+        // only the producer sets FRESH, it is still set
+        let middle = self.shared.middle.swap(self.front, Ordering::AcqRel);
+        self.front = middle & !FRESH;
+        true
+    }
+
+    /// Remove the oldest element of the front buffer and return it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        self.buffer_mut().next()
+    }
+
+    /// The buffer read by the consumer.
+    pub fn buffer(&self) -> &CircularBuffer<T> {
+        unsafe { &*self.shared.rings[self.front].get() }
+    }
+
+    /// The buffer read by the consumer, use it to drain the elements.
+    pub fn buffer_mut(&mut self) -> &mut CircularBuffer<T> {
+        unsafe { &mut *self.shared.rings[self.front].get() }
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for DoubleBuffer<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DoubleBuffer")
+            .field("front", &self.front)
+            .field("back", &self.back)
+            .finish()
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{Allocator, CircularBuffer, Global};
+
+/// A draining iterator over a range of elements of a CircularBuffer.
+///
+/// It is created by the `CircularBuffer::drain` method.
+///
+/// When the iterator is dropped, the elements not consumed yet are dropped and the buffer gets
+/// compacted. If the iterator is leaked, with `std::mem::forget` for instance, the buffer loses the
+/// drained range and every element after it, but it is left in a consistent state.
+pub struct Drain<'a, T, P = crate::OldestFirst, A: Allocator = Global> {
+    buffer: &'a mut CircularBuffer<T, P, A>,
+    // the buffer len is set to `start` while draining
+    start: usize,
+    // next element returned by `next`
+    front: usize,
+    // one past the element returned by `next_back`
+    back: usize,
+    // the elements after the drained range, they starts at `tail_start`
+    tail_start: usize,
+    tail_len: usize,
+}
+
+impl<'a, T, P, A: Allocator> Drain<'a, T, P, A> {
+    pub(crate) fn new(buffer: &'a mut CircularBuffer<T, P, A>, start: usize, end: usize) -> Self {
+        let len = buffer.len();
+        buffer.set_len(start);
+        Drain {
+            buffer,
+            start,
+            front: start,
+            back: end,
+            tail_start: end,
+            tail_len: len - end,
+        }
+    }
+
+    // move the elements around the drained range next to each other, moving the shorter side
+    fn close_gap(&mut self) {
+        let drained = self.tail_start - self.start;
+        if drained == 0 {
+            self.buffer.set_len(self.start + self.tail_len);
+            return;
+        }
+        if self.start <= self.tail_len {
+            for i in (0..self.start).rev() {
+                unsafe {
+                    let from = self.buffer.ptr_at(i);
+                    let to = self.buffer.ptr_at(i + drained);
+                    core::ptr::copy_nonoverlapping(from, to, 1);
+                }
+            }
+            self.buffer.r_inc_of(drained);
+        } else {
+            for i in 0..self.tail_len {
+                unsafe {
+                    let from = self.buffer.ptr_at(self.tail_start + i);
+                    let to = self.buffer.ptr_at(self.start + i);
+                    core::ptr::copy_nonoverlapping(from, to, 1);
+                }
+            }
+        }
+        self.buffer.set_len(self.start + self.tail_len);
+    }
+
+    // write the elements of `replace_with` in the drained range, once every drained element is
+    // consumed, then in the free space moved in front of the tail. The elements that do not fit
+    // are not taken from `replace_with`.
+    pub(crate) fn fill_gap<I: Iterator<Item = T>>(&mut self, replace_with: &mut I) {
+        if !self.fill_range(replace_with) {
+            return;
+        }
+        let free = self.buffer.size - (self.tail_start + self.tail_len);
+        if free == 0 {
+            return;
+        }
+        let next = match replace_with.next() {
+            Some(element) => element,
+            None => return,
+        };
+        for i in (0..self.tail_len).rev() {
+            unsafe {
+                let from = self.buffer.ptr_at(self.tail_start + i);
+                let to = self.buffer.ptr_at(self.tail_start + free + i);
+                core::ptr::copy_nonoverlapping(from, to, 1);
+            }
+        }
+        self.tail_start += free;
+        self.write_at_start(next);
+        self.fill_range(replace_with);
+    }
+
+    // returns `true` if the drained range is filled, `false` if `replace_with` ran out first
+    fn fill_range<I: Iterator<Item = T>>(&mut self, replace_with: &mut I) -> bool {
+        while self.start < self.tail_start {
+            match replace_with.next() {
+                Some(element) => self.write_at_start(element),
+                None => return false,
+            }
+        }
+        true
+    }
+
+    // the element written is part of the buffer right away, so the gap is closed around it even
+    // if `replace_with` panics
+    fn write_at_start(&mut self, element: T) {
+        unsafe { self.buffer.ptr_at(self.start).write(element) };
+        self.start += 1;
+        self.buffer.set_len(self.start);
+    }
+}
+
+impl<'a, T, P, A: Allocator> Iterator for Drain<'a, T, P, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        let element = unsafe { self.buffer.ptr_at(self.front).read() };
+        self.front += 1;
+        Some(element)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T, P, A: Allocator> DoubleEndedIterator for Drain<'a, T, P, A> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(unsafe { self.buffer.ptr_at(self.back).read() })
+    }
+}
+
+impl<'a, T, P, A: Allocator> ExactSizeIterator for Drain<'a, T, P, A> {}
+
+impl<'a, T, P, A: Allocator> core::iter::FusedIterator for Drain<'a, T, P, A> {}
+
+impl<'a, T, P, A: Allocator> Drop for Drain<'a, T, P, A> {
+    fn drop(&mut self) {
+        // close the gap even if the destructor of one of the elements panics
+        struct Guard<'r, 'a, T, P, A: Allocator>(&'r mut Drain<'a, T, P, A>);
+
+        impl<'r, 'a, T, P, A: Allocator> Drop for Guard<'r, 'a, T, P, A> {
+            fn drop(&mut self) {
+                self.0.close_gap();
+            }
+        }
+
+        let guard = Guard(self);
+        for element in &mut *guard.0 {
+            drop(element);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use rustfft::num_complex::Complex;
+use rustfft::{FftNum, FftPlanner};
+
+use crate::{Allocator, CircularBuffer};
+
+/// Window function applied to the samples before the FFT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Window {
+    /// No window, the samples are used as they are.
+    Rectangular,
+    Hann,
+    Hamming,
+    Blackman,
+}
+
+impl Window {
+    fn coefficient(self, n: usize, len: usize) -> f64 {
+        if len < 2 {
+            return 1.0;
+        }
+        let x = 2.0 * core::f64::consts::PI * n as f64 / (len - 1) as f64;
+        match self {
+            Window::Rectangular => 1.0,
+            Window::Hann => 0.5 - 0.5 * x.cos(),
+            Window::Hamming => 0.54 - 0.46 * x.cos(),
+            Window::Blackman => 0.42 - 0.5 * x.cos() + 0.08 * (2.0 * x).cos(),
+        }
+    }
+}
+
+/// The state reused across calls to `CircularBuffer::spectrum_into`.
+///
+/// It holds the FFT planner, that caches the FFT plans, the scratch space of the FFT and the
+/// coefficients of the window, so after the first call with a given length no allocation is done.
+pub struct SpectrumScratch<T: FftNum> {
+    planner: FftPlanner<T>,
+    scratch: Vec<Complex<T>>,
+    window: Window,
+    coefficients: Vec<T>,
+}
+
+impl<T: FftNum> SpectrumScratch<T> {
+    pub fn new(window: Window) -> Self {
+        SpectrumScratch {
+            planner: FftPlanner::new(),
+            scratch: Vec::new(),
+            window,
+            coefficients: Vec::new(),
+        }
+    }
+
+    fn coefficients(&mut self, len: usize) -> &[T] {
+        if self.coefficients.len() != len {
+            let window = self.window;
+            self.coefficients.clear();
+            self.coefficients.extend(
+                (0..len).map(|n| T::from_f64(window.coefficient(n, len)).unwrap_or_else(T::one)),
+            );
+        }
+        &self.coefficients
+    }
+}
+
+impl<T: FftNum, P, A: Allocator> CircularBuffer<T, P, A> {
+    /// Compute the FFT of the newest `out.len()` samples, after applying the window of `scratch`.
+    ///
+    /// If the CircularBuffer holds less than `out.len()` samples, the missing oldest samples are
+    /// considered zeros. Returns the number of samples taken from the CircularBuffer.
+    ///
+    /// The CircularBuffer is not modified.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    /// use rustfft::num_complex::Complex;
+    ///
+    /// let mut buffer = CircularBuffer::new(8);
+    /// for _ in 0..8 {
+    ///     buffer.push(1.0f32);
+    /// }
+    /// let mut scratch = SpectrumScratch::new(Window::Rectangular);
+    /// let mut out = vec![Complex::new(0.0, 0.0); 4];
+    /// assert_eq!(4, buffer.spectrum_into(&mut scratch, &mut out));
+    /// assert_eq!(Complex::new(4.0, 0.0), out[0]);
+    /// assert_eq!(Complex::new(0.0, 0.0), out[1]);
+    /// ```
+    pub fn spectrum_into(&self, scratch: &mut SpectrumScratch<T>, out: &mut [Complex<T>]) -> usize {
+        let n = out.len();
+        if n == 0 {
+            return 0;
+        }
+        let len = self.len();
+        let samples = core::cmp::min(n, len);
+        let padding = n - samples;
+
+        let coefficients = scratch.coefficients(n);
+        for (i, slot) in out.iter_mut().enumerate() {
+            let sample = if i < padding {
+                T::zero()
+            } else {
+                unsafe { *self.ptr_at(len - samples + i - padding) }
+            };
+            *slot = Complex::new(sample * coefficients[i], T::zero());
+        }
+
+        let fft = scratch.planner.plan_fft_forward(n);
+        let scratch_len = fft.get_inplace_scratch_len();
+        if scratch.scratch.len() < scratch_len {
+            scratch
+                .scratch
+                .resize(scratch_len, Complex::new(T::zero(), T::zero()));
+        }
+        fft.process_with_scratch(out, &mut scratch.scratch[..scratch_len]);
+        samples
+    }
+}
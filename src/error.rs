@@ -0,0 +1,168 @@
+use core::alloc::LayoutError;
+
+/// The allocation of the array backing a CircularBuffer failed.
+///
+/// It reports the requested capacity and the size of the elements, so the caller knows how much
+/// memory was requested.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllocError {
+    capacity: usize,
+    element_size: usize,
+    // set if the size in bytes of the array does not fit in an `isize`
+    layout: Option<LayoutError>,
+}
+
+impl AllocError {
+    #[cfg(feature = "alloc")]
+    pub(crate) fn capacity_overflow(capacity: usize, element_size: usize, error: LayoutError) -> Self {
+        AllocError {
+            capacity,
+            element_size,
+            layout: Some(error),
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn out_of_memory(capacity: usize, element_size: usize) -> Self {
+        AllocError {
+            capacity,
+            element_size,
+            layout: None,
+        }
+    }
+
+    /// The number of elements requested.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The size in bytes of each element.
+    pub fn element_size(&self) -> usize {
+        self.element_size
+    }
+
+    /// `true` if the requested size in bytes overflows, `false` if the allocator failed to
+    /// provide the memory.
+    pub fn is_capacity_overflow(&self) -> bool {
+        self.layout.is_some()
+    }
+}
+
+impl core::fmt::Display for AllocError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.is_capacity_overflow() {
+            write!(
+                f,
+                "capacity overflow: {} elements of {} bytes do not fit in memory",
+                self.capacity, self.element_size
+            )
+        } else {
+            write!(
+                f,
+                "failed to allocate {} elements of {} bytes",
+                self.capacity, self.element_size
+            )
+        }
+    }
+}
+
+impl core::error::Error for AllocError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        self.layout
+            .as_ref()
+            .map(|e| e as &(dyn core::error::Error + 'static))
+    }
+}
+
+/// A bulk insertion was given more elements than the CircularBuffer can hold.
+///
+/// Returned by `CircularBuffer::try_extend_from_slice`, the CircularBuffer is left untouched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError {
+    capacity: usize,
+    requested: usize,
+}
+
+impl CapacityError {
+    #[cfg(feature = "alloc")]
+    pub(crate) fn new(capacity: usize, requested: usize) -> Self {
+        CapacityError { capacity, requested }
+    }
+
+    /// The capacity of the CircularBuffer.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of elements that were going to be inserted.
+    pub fn requested(&self) -> usize {
+        self.requested
+    }
+}
+
+impl core::fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} elements do not fit in a buffer of capacity {}",
+            self.requested, self.capacity
+        )
+    }
+}
+
+impl core::error::Error for CapacityError {}
+
+/// The storage given to `BorrowedCircularBuffer::self_test` did not return the values written.
+///
+/// It reports the check that failed, the value expected and the one read, `None` if an element
+/// was missing. When the length of the buffer is wrong, the values are the lengths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelfTestError {
+    check: usize,
+    expected: u32,
+    found: Option<u32>,
+}
+
+impl SelfTestError {
+    pub(crate) fn new(check: usize, expected: u32, found: Option<u32>) -> Self {
+        SelfTestError {
+            check,
+            expected,
+            found,
+        }
+    }
+
+    /// The number of checks that passed before this one.
+    pub fn check(&self) -> usize {
+        self.check
+    }
+
+    /// The value that was expected.
+    pub fn expected(&self) -> u32 {
+        self.expected
+    }
+
+    /// The value read, `None` if there was nothing to read.
+    pub fn found(&self) -> Option<u32> {
+        self.found
+    }
+}
+
+impl core::fmt::Display for SelfTestError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.found {
+            Some(found) => write!(
+                f,
+                "self test failed at check {}: expected {:#010x}, found {:#010x}",
+                self.check, self.expected, found
+            ),
+            None => write!(
+                f,
+                "self test failed at check {}: expected {:#010x}, found nothing",
+                self.check, self.expected
+            ),
+        }
+    }
+}
+
+impl core::error::Error for SelfTestError {}
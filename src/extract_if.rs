@@ -0,0 +1,86 @@
+use crate::{Allocator, CircularBuffer, Global};
+
+/// An iterator that removes and yields the elements matching a predicate.
+///
+/// It is created by the `CircularBuffer::extract_if` method.
+///
+/// The elements are examined lazily, when the iterator is dropped the elements not examined yet
+/// are left in the CircularBuffer. If the iterator is leaked, with `std::mem::forget` for
+/// instance, the buffer is left empty but in a consistent state.
+pub struct ExtractIf<'a, T, P, F, A: Allocator = Global>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    buffer: &'a mut CircularBuffer<T, P, A>,
+    // next element to examine
+    idx: usize,
+    // number of elements extracted so far
+    del: usize,
+    old_len: usize,
+    pred: F,
+}
+
+impl<'a, T, P, F, A: Allocator> ExtractIf<'a, T, P, F, A>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    pub(crate) fn new(buffer: &'a mut CircularBuffer<T, P, A>, pred: F) -> Self {
+        let old_len = buffer.len();
+        buffer.set_len(0);
+        ExtractIf {
+            buffer,
+            idx: 0,
+            del: 0,
+            old_len,
+            pred,
+        }
+    }
+}
+
+impl<'a, T, P, F, A: Allocator> Iterator for ExtractIf<'a, T, P, F, A>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while self.idx < self.old_len {
+            let current = self.buffer.ptr_at(self.idx);
+            let extract = (self.pred)(unsafe { &mut *current });
+            self.idx += 1;
+            if extract {
+                self.del += 1;
+                return Some(unsafe { current.read() });
+            }
+            if self.del > 0 {
+                unsafe {
+                    let hole = self.buffer.ptr_at(self.idx - 1 - self.del);
+                    core::ptr::copy_nonoverlapping(current, hole, 1);
+                }
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.old_len - self.idx))
+    }
+}
+
+impl<'a, T, P, F, A: Allocator> Drop for ExtractIf<'a, T, P, F, A>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    fn drop(&mut self) {
+        if self.del > 0 {
+            for i in self.idx..self.old_len {
+                unsafe {
+                    let from = self.buffer.ptr_at(i);
+                    let to = self.buffer.ptr_at(i - self.del);
+                    core::ptr::copy_nonoverlapping(from, to, 1);
+                }
+            }
+        }
+        self.buffer.set_len(self.old_len - self.del);
+    }
+}
@@ -0,0 +1,67 @@
+#[cfg(feature = "alloc")]
+use crate::{Allocator, CircularBuffer};
+use crate::{BorrowedCircularBuffer, StaticCircularBuffer};
+
+#[cfg(feature = "alloc")]
+impl<T, P, A: Allocator> CircularBuffer<T, P, A> {
+    /// Push the oldest elements of the CircularBuffer into a `heapless::Vec`, like `fill` does
+    /// for a `Vec`, and returns the number of elements pushed.
+    ///
+    /// Only the free slots of `return_vector` are filled, it never fails: the elements that do
+    /// not fit are left in the CircularBuffer.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(4);
+    /// buffer.extend_from_slice(&[1, 2, 3, 4]);
+    /// let mut v: heapless::Vec<u32, 3> = heapless::Vec::new();
+    /// assert_eq!(3, buffer.fill_heapless(&mut v));
+    /// assert_eq!(&[1, 2, 3], v.as_slice());
+    /// assert_eq!(1, buffer.len());
+    /// ```
+    pub fn fill_heapless<const N: usize>(
+        &mut self,
+        return_vector: &mut heapless::Vec<T, N>,
+    ) -> usize {
+        fill(self, return_vector)
+    }
+}
+
+impl<T, const M: usize> StaticCircularBuffer<T, M> {
+    /// Push the oldest elements into a `heapless::Vec`, like `CircularBuffer::fill_heapless`.
+    pub fn fill_heapless<const N: usize>(
+        &mut self,
+        return_vector: &mut heapless::Vec<T, N>,
+    ) -> usize {
+        fill(self, return_vector)
+    }
+}
+
+impl<'a, T> BorrowedCircularBuffer<'a, T> {
+    /// Push the oldest elements into a `heapless::Vec`, like `CircularBuffer::fill_heapless`.
+    pub fn fill_heapless<const N: usize>(
+        &mut self,
+        return_vector: &mut heapless::Vec<T, N>,
+    ) -> usize {
+        fill(self, return_vector)
+    }
+}
+
+fn fill<T, const N: usize>(
+    elements: &mut impl Iterator<Item = T>,
+    return_vector: &mut heapless::Vec<T, N>,
+) -> usize {
+    let mut i = 0;
+    while !return_vector.is_full() {
+        match elements.next() {
+            Some(element) => {
+                // the vector is not full, there is room for the element
+                unsafe { return_vector.push_unchecked(element) };
+                i += 1;
+            }
+            None => return i,
+        }
+    }
+    i
+}
@@ -0,0 +1,170 @@
+use crate::{Allocator, CircularBuffer, EvictionPolicy, Global};
+
+/// Wraps a CircularBuffer and counts the elements pushed, popped and overwritten, to know how
+/// often the buffer loses elements, along with the highest number of elements it held.
+///
+/// An element is counted as overwritten when a push does not make the buffer longer: the oldest
+/// element was overwritten or, with other policies, an element was evicted or the new one was
+/// rejected.
+///
+/// `push`, `extend_from_slice`, `Extend` and `pop`, also through `Iterator`, are counted. The
+/// wrapper only lends the buffer immutably, anything else, like `drain` or `clear`, needs the
+/// buffer back from `into_inner`.
+///
+/// ```
+/// use rbl_circular_buffer::*;
+///
+/// let mut ring = Instrumented::new(CircularBuffer::new(2));
+/// for message in 0..5 {
+///     ring.push(message);
+/// }
+/// ring.pop();
+/// assert_eq!(5, ring.total_pushed());
+/// assert_eq!(1, ring.total_popped());
+/// assert_eq!(3, ring.overwritten_count());
+/// assert_eq!(2, ring.high_watermark());
+///
+/// ring.reset_stats();
+/// assert_eq!(0, ring.overwritten_count());
+/// assert_eq!(1, ring.high_watermark());
+/// assert_eq!(1, ring.buffer().len());
+/// ```
+pub struct Instrumented<T, P = crate::OldestFirst, A: Allocator = Global> {
+    buffer: CircularBuffer<T, P, A>,
+    pushed: u64,
+    popped: u64,
+    overwritten: u64,
+    high_watermark: usize,
+}
+
+impl<T, P, A: Allocator> Instrumented<T, P, A> {
+    /// Start counting the operations on `buffer`, the elements it already holds are not counted
+    /// but they are part of the high watermark.
+    pub fn new(buffer: CircularBuffer<T, P, A>) -> Self {
+        let high_watermark = buffer.len();
+        Instrumented {
+            buffer,
+            pushed: 0,
+            popped: 0,
+            overwritten: 0,
+            high_watermark,
+        }
+    }
+
+    /// Push a new element into the buffer, like `CircularBuffer::push`, and count it.
+    pub fn push(&mut self, value: T) -> usize
+    where
+        P: EvictionPolicy<T>,
+    {
+        let len = self.buffer.len();
+        let empty_spots = self.buffer.push(value);
+        self.pushed += 1;
+        if self.buffer.len() == len {
+            self.overwritten += 1;
+        }
+        self.high_watermark = core::cmp::max(self.high_watermark, self.buffer.len());
+        empty_spots
+    }
+
+    /// Push all the `values` into the buffer, like `CircularBuffer::extend_from_slice`, and count
+    /// them.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut ring = Instrumented::new(CircularBuffer::new(4));
+    /// ring.extend_from_slice(&[1, 2, 3, 4, 5, 6]);
+    /// assert_eq!(6, ring.total_pushed());
+    /// assert_eq!(2, ring.overwritten_count());
+    /// assert_eq!(4, ring.high_watermark());
+    /// ```
+    pub fn extend_from_slice(&mut self, values: &[T]) -> usize
+    where
+        T: Copy,
+        P: EvictionPolicy<T>,
+    {
+        let len = self.buffer.len();
+        let empty_spots = self.buffer.extend_from_slice(values);
+        // pushing never makes the buffer shorter, every element that did not make it longer was
+        // lost
+        let added = self.buffer.len() - len;
+        self.pushed += values.len() as u64;
+        self.overwritten += (values.len() - added) as u64;
+        self.high_watermark = core::cmp::max(self.high_watermark, self.buffer.len());
+        empty_spots
+    }
+
+    /// Remove the oldest element and return it, the element is counted only if one is returned.
+    pub fn pop(&mut self) -> Option<T> {
+        let element = self.buffer.next();
+        if element.is_some() {
+            self.popped += 1;
+        }
+        element
+    }
+
+    /// Returns the number of elements pushed since the counters were reset.
+    pub fn total_pushed(&self) -> u64 {
+        self.pushed
+    }
+
+    /// Returns the number of elements popped since the counters were reset.
+    pub fn total_popped(&self) -> u64 {
+        self.popped
+    }
+
+    /// Returns the number of elements lost since the counters were reset, because a push found
+    /// the buffer full.
+    pub fn overwritten_count(&self) -> u64 {
+        self.overwritten
+    }
+
+    /// Returns the highest number of elements held by the buffer since the counters were reset.
+    ///
+    /// After a soak test, a high watermark well below the capacity means the buffer can be
+    /// smaller, one equal to the capacity, along with elements overwritten, that it is too small.
+    pub fn high_watermark(&self) -> usize {
+        self.high_watermark
+    }
+
+    /// Set all the counters back to zero, and the high watermark to the current number of
+    /// elements, the elements in the buffer are left untouched.
+    pub fn reset_stats(&mut self) {
+        self.pushed = 0;
+        self.popped = 0;
+        self.overwritten = 0;
+        self.high_watermark = self.buffer.len();
+    }
+
+    /// The buffer being counted.
+    pub fn buffer(&self) -> &CircularBuffer<T, P, A> {
+        &self.buffer
+    }
+
+    /// Stop counting, returning the buffer.
+    pub fn into_inner(self) -> CircularBuffer<T, P, A> {
+        self.buffer
+    }
+}
+
+/// Push and count every element of the iterator.
+impl<T, P: EvictionPolicy<T>, A: Allocator> Extend<T> for Instrumented<T, P, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+/// Pop and count the elements, from the oldest.
+impl<T, P, A: Allocator> Iterator for Instrumented<T, P, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.buffer.size_hint()
+    }
+}
@@ -0,0 +1,106 @@
+use std::io;
+
+use crate::{Allocator, CircularBuffer, EvictionPolicy};
+
+impl<P, A: Allocator> CircularBuffer<u8, P, A> {
+    /// Returns the bytes, from the oldest, as two `IoSlice`s: the one up to the end of the array
+    /// and the one wrapped around to its start, which may be empty.
+    ///
+    /// A single `write_vectored` can then send all the bytes, `advance_read` removes the ones
+    /// written.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    /// use std::io::Write;
+    ///
+    /// let mut buffer = CircularBuffer::new(4);
+    /// buffer.extend_from_slice(b"abcdef");
+    /// let mut socket = Vec::new();
+    /// let n = socket.write_vectored(&buffer.as_io_slices()).unwrap();
+    /// buffer.advance_read(n);
+    /// assert_eq!(b"cdef", &socket[..]);
+    /// assert!(buffer.is_empty());
+    /// ```
+    pub fn as_io_slices(&self) -> [io::IoSlice<'_>; 2] {
+        let (older, newer) = self.as_raw_slices();
+        [io::IoSlice::new(older), io::IoSlice::new(newer)]
+    }
+}
+
+/// Read the oldest bytes, removing them from the CircularBuffer.
+///
+/// An empty CircularBuffer reads 0 bytes, the end of the stream.
+///
+/// ```
+/// use rbl_circular_buffer::*;
+/// use std::io::{Read, Write};
+///
+/// let mut buffer = CircularBuffer::new(8);
+/// buffer.write_all(b"hello").unwrap();
+/// let mut s = String::new();
+/// buffer.read_to_string(&mut s).unwrap();
+/// assert_eq!("hello", s);
+/// ```
+impl<P, A: Allocator> io::Read for CircularBuffer<u8, P, A> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Ok(self.read_into(buf))
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        let mut n = 0;
+        for buf in bufs {
+            n += self.read_into(buf);
+            if self.is_empty() {
+                break;
+            }
+        }
+        Ok(n)
+    }
+}
+
+/// Push the bytes, with the same semantics of `CircularBuffer::extend_from_slice`.
+///
+/// It always accepts the whole slice: when the CircularBuffer is full its policy decides which
+/// bytes are lost.
+impl<P: EvictionPolicy<u8>, A: Allocator> io::Write for CircularBuffer<u8, P, A> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        let mut n = 0;
+        for buf in bufs {
+            self.extend_from_slice(buf);
+            n += buf.len();
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Read the bytes in place: `fill_buf` returns the oldest contiguous bytes, up to the end of the
+/// array, and `consume` removes them, without copying.
+///
+/// ```
+/// use rbl_circular_buffer::*;
+/// use std::io::BufRead;
+///
+/// let mut buffer = CircularBuffer::new(16);
+/// buffer.extend_from_slice(b"GET /\r\nHost: a\r\n");
+/// let lines: Vec<String> = buffer.lines().map(|l| l.unwrap()).collect();
+/// assert_eq!(vec!["GET /", "Host: a"], lines);
+/// ```
+impl<P, A: Allocator> io::BufRead for CircularBuffer<u8, P, A> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let (older, _) = self.as_raw_slices();
+        Ok(older)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.advance_read(amt);
+    }
+}
@@ -0,0 +1,195 @@
+use core::marker::PhantomData;
+
+#[cfg(feature = "alloc")]
+use crate::{Allocator, CircularBuffer};
+
+/// An iterator over references to the elements of a CircularBuffer, from the oldest to the newest.
+///
+/// The elements are not consumed. It is created by the `iter` method of `CircularBuffer`,
+/// `StaticCircularBuffer` and `BorrowedCircularBuffer`.
+pub struct Iter<'a, T> {
+    buffer: *const T,
+    size: usize,
+    r: usize,
+    // logical positions, 0 is the oldest element
+    front: usize,
+    back: usize,
+    _marker: PhantomData<&'a T>,
+}
+
+// like `&'a T`, it only hands out shared references to the elements
+unsafe impl<'a, T: Sync> Send for Iter<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
+
+impl<'a, T> Iter<'a, T> {
+    #[cfg(feature = "alloc")]
+    pub(crate) fn new<P, A: Allocator>(buffer: &'a CircularBuffer<T, P, A>) -> Self {
+        Iter::from_raw(buffer.as_mut_ptr(), buffer.size, buffer.r, buffer.len())
+    }
+
+    // `buffer` must hold `len` initialized elements starting at `r`, wrapping at `size`, and
+    // they must not change for `'a`
+    pub(crate) fn from_raw(buffer: *const T, size: usize, r: usize, len: usize) -> Self {
+        Iter {
+            buffer,
+            size,
+            r,
+            front: 0,
+            back: len,
+            _marker: PhantomData,
+        }
+    }
+
+    // keep only the last `n` elements not yet returned
+    #[cfg(feature = "alloc")]
+    pub(crate) fn last(mut self, n: usize) -> Self {
+        self.front = core::cmp::max(self.front, self.back.saturating_sub(n));
+        self
+    }
+
+    fn get(&self, i: usize) -> &'a T {
+        unsafe { &*self.buffer.add((self.r + i) % self.size) }
+    }
+}
+
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter { ..*self }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.front == self.back {
+            return None;
+        }
+        let element = self.get(self.front);
+        self.front += 1;
+        Some(element)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.get(self.back))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> core::iter::FusedIterator for Iter<'a, T> {}
+
+/// An iterator over references to the elements of a CircularBuffer, from the newest to the oldest.
+///
+/// The elements are not consumed. It is created by the `iter_rev` method of `CircularBuffer`,
+/// `StaticCircularBuffer` and `BorrowedCircularBuffer`.
+pub struct IterRev<'a, T> {
+    iter: Iter<'a, T>,
+}
+
+impl<'a, T> IterRev<'a, T> {
+    pub(crate) fn new(iter: Iter<'a, T>) -> Self {
+        IterRev { iter }
+    }
+
+    /// Pair each element with its age: 0 for the newest element, 1 for the one pushed before it
+    /// and so on.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(3);
+    /// buffer.extend_from_slice(&["a", "b", "a", "c"]);
+    /// let last_a = buffer.iter_rev().enumerate_from_newest().find(|(_, e)| **e == "a");
+    /// assert_eq!(Some((1, &"a")), last_a);
+    /// ```
+    pub fn enumerate_from_newest(self) -> core::iter::Enumerate<Self> {
+        self.enumerate()
+    }
+}
+
+impl<'a, T> Clone for IterRev<'a, T> {
+    fn clone(&self) -> Self {
+        IterRev {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for IterRev<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.iter.next_back()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterRev<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.iter.next()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterRev<'a, T> {}
+
+impl<'a, T> core::iter::FusedIterator for IterRev<'a, T> {}
+
+/// An iterator over the pairs of consecutive elements of a CircularBuffer.
+///
+/// It is created by the `CircularBuffer::iter_pairs` method.
+pub struct Pairs<'a, T> {
+    iter: Iter<'a, T>,
+    previous: Option<&'a T>,
+}
+
+impl<'a, T> Pairs<'a, T> {
+    #[cfg(feature = "alloc")]
+    pub(crate) fn new<P, A: Allocator>(buffer: &'a CircularBuffer<T, P, A>) -> Self {
+        let mut iter = Iter::new(buffer);
+        let previous = iter.next();
+        Pairs { iter, previous }
+    }
+}
+
+impl<'a, T> Clone for Pairs<'a, T> {
+    fn clone(&self) -> Self {
+        Pairs {
+            iter: self.iter.clone(),
+            previous: self.previous,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Pairs<'a, T> {
+    type Item = (&'a T, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let previous = self.previous?;
+        let current = self.iter.next()?;
+        self.previous = Some(current);
+        Some((previous, current))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Pairs<'a, T> {}
+
+impl<'a, T> core::iter::FusedIterator for Pairs<'a, T> {}
@@ -0,0 +1,138 @@
+use alloc::vec::Vec;
+
+use crate::{Allocator, CircularBuffer, EvictionPolicy};
+
+/// An operation executed against a CircularBuffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation<T> {
+    Push(T),
+    Pop,
+    Clear,
+}
+
+/// An entry of the journal, the operation along with its sequence number.
+///
+/// Sequence numbers start from 0 and are incremented by one for every operation recorded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry<T> {
+    pub sequence: u64,
+    pub operation: Operation<T>,
+}
+
+/// Where the Recorder writes the journal entries.
+pub trait JournalSink<T> {
+    fn record(&mut self, entry: Entry<T>);
+}
+
+impl<T> JournalSink<T> for Vec<Entry<T>> {
+    fn record(&mut self, entry: Entry<T>) {
+        self.push(entry);
+    }
+}
+
+/// Wraps a CircularBuffer and records every push, pop and clear into a JournalSink.
+///
+/// The journal can be used with `CircularBuffer::replay` to reconstruct the state of the buffer,
+/// which helps debugging ordering issues that are hard to reproduce.
+///
+/// ```
+/// use rbl_circular_buffer::*;
+///
+/// let mut recorder = Recorder::new(CircularBuffer::new(2), Vec::new());
+/// recorder.push(1);
+/// recorder.push(2);
+/// recorder.pop();
+/// recorder.push(3);
+///
+/// let (buffer, journal) = recorder.into_parts();
+///
+/// let mut replayed = CircularBuffer::new(2);
+/// replayed.replay(journal);
+/// assert_eq!(format!("{}", buffer), format!("{}", replayed));
+/// ```
+pub struct Recorder<T, S: JournalSink<T>> {
+    buffer: CircularBuffer<T>,
+    sink: S,
+    sequence: u64,
+}
+
+impl<T, S: JournalSink<T>> Recorder<T, S> {
+    /// Start recording the operations on `buffer` into `sink`.
+    ///
+    /// The elements already in the buffer are not recorded, to replay the journal start from a
+    /// buffer in the same state of `buffer`.
+    pub fn new(buffer: CircularBuffer<T>, sink: S) -> Self {
+        Recorder {
+            buffer,
+            sink,
+            sequence: 0,
+        }
+    }
+
+    fn record(&mut self, operation: Operation<T>) {
+        let entry = Entry {
+            sequence: self.sequence,
+            operation,
+        };
+        self.sequence += 1;
+        self.sink.record(entry);
+    }
+
+    /// Pop the oldest element from the buffer, the pop is recorded only if an element is returned.
+    pub fn pop(&mut self) -> Option<T> {
+        let element = self.buffer.next();
+        if element.is_some() {
+            self.record(Operation::Pop);
+        }
+        element
+    }
+
+    /// Clear the buffer and record the operation.
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.record(Operation::Clear);
+    }
+
+    /// The buffer being recorded.
+    pub fn buffer(&self) -> &CircularBuffer<T> {
+        &self.buffer
+    }
+
+    /// The sink where the operations are recorded.
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    /// Stop recording, returning the buffer and the sink.
+    pub fn into_parts(self) -> (CircularBuffer<T>, S) {
+        (self.buffer, self.sink)
+    }
+}
+
+impl<T: Clone, S: JournalSink<T>> Recorder<T, S> {
+    /// Push a new element into the buffer and record the operation.
+    pub fn push(&mut self, value: T) -> usize {
+        self.record(Operation::Push(value.clone()));
+        self.buffer.push(value)
+    }
+}
+
+impl<T, P: EvictionPolicy<T>, A: Allocator> CircularBuffer<T, P, A> {
+    /// Apply, in order, all the operations of the journal.
+    ///
+    /// Replaying a journal against a buffer with the same size and content of the buffer that was
+    /// recorded will bring the two buffers in the same state.
+    pub fn replay<I: IntoIterator<Item = Entry<T>>>(&mut self, journal: I) {
+        for entry in journal {
+            match entry.operation {
+                Operation::Push(value) => {
+                    self.push(value);
+                }
+                Operation::Pop => {
+                    self.next();
+                }
+                Operation::Clear => self.clear(),
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use crate::iter::Iter;
+
+/// Formats only the newest elements of a CircularBuffer with `Display`.
+///
+/// It is created by the `CircularBuffer::display_last` method.
+pub struct DisplayLast<'a, T> {
+    iter: Iter<'a, T>,
+    skipped: usize,
+}
+
+/// Formats only the newest elements of a CircularBuffer with `Debug`.
+///
+/// It is created by the `CircularBuffer::debug_last` method.
+pub struct DebugLast<'a, T> {
+    iter: Iter<'a, T>,
+    skipped: usize,
+}
+
+impl<'a, T> DisplayLast<'a, T> {
+    pub(crate) fn new(iter: Iter<'a, T>, k: usize) -> Self {
+        let len = iter.len();
+        let iter = iter.last(k);
+        DisplayLast {
+            skipped: len - iter.len(),
+            iter,
+        }
+    }
+}
+
+impl<'a, T> DebugLast<'a, T> {
+    pub(crate) fn new(iter: Iter<'a, T>, k: usize) -> Self {
+        let len = iter.len();
+        let iter = iter.last(k);
+        DebugLast {
+            skipped: len - iter.len(),
+            iter,
+        }
+    }
+}
+
+impl<'a, T: core::fmt::Display> core::fmt::Display for DisplayLast<'a, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fmt_last(f, self.iter.clone(), self.skipped, core::fmt::Display::fmt)
+    }
+}
+
+impl<'a, T: core::fmt::Debug> core::fmt::Debug for DebugLast<'a, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fmt_last(f, self.iter.clone(), self.skipped, core::fmt::Debug::fmt)
+    }
+}
+
+// the older elements left out are marked with a leading `..`
+fn fmt_last<'a, T>(
+    f: &mut core::fmt::Formatter<'_>,
+    iter: Iter<'a, T>,
+    skipped: usize,
+    fmt: fn(&T, &mut core::fmt::Formatter<'_>) -> core::fmt::Result,
+) -> core::fmt::Result {
+    if iter.len() == 0 && skipped == 0 {
+        return write!(f, "CircularBuffer(<empty>)");
+    }
+    write!(f, "CircularBuffer(")?;
+    let mut first = true;
+    if skipped > 0 {
+        write!(f, "..")?;
+        first = false;
+    }
+    for element in iter {
+        if !first {
+            write!(f, ", ")?;
+        }
+        fmt(element, f)?;
+        first = false;
+    }
+    write!(f, ")")
+}
@@ -100,116 +100,1971 @@
 //! assert_eq!(4, buffer.len())
 //! ```
 //!
+//! ## Eviction policies
+//!
+//! By default, when the buffer is full, the oldest element is overwritten. A different `EvictionPolicy`
+//! can be passed to `with_policy`: `Reject` drops the new elements, `Priority` keeps the greatest
+//! elements and `Random` evicts a random element. `OnEvict` wraps a policy to hand the elements
+//! that do not fit to a function, instead of dropping them.
+//!
+//! ```
+//! use rbl_circular_buffer::*;
+//!
+//! let mut buffer = CircularBuffer::with_policy(3, Priority);
+//! for i in [5, 1, 7, 3] {
+//!     buffer.push(i);
+//! }
+//!
+//! let v: Vec<u32> = buffer.collect();
+//! assert_eq!(vec![5, 7, 3], v);
+//! ```
+//!
+//! ## no_std
+//!
+//! The crate is `no_std` when the default `std` feature is disabled. With the `alloc` feature the
+//! heap allocated `CircularBuffer` is still available, without it only `StaticCircularBuffer`
+//! and `BorrowedCircularBuffer` are, which never allocate.
+//!
+//! ```toml
+//! rbl_circular_buffer = { version = "0.1", default-features = false, features = ["alloc"] }
+//! ```
+//!
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(feature = "portable_simd", feature(portable_simd))]
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::mem::MaybeUninit;
+#[cfg(feature = "alloc")]
+use core::ptr::NonNull;
+
+#[cfg(feature = "alloc")]
+#[macro_use]
+mod macros;
+
+#[cfg(feature = "alloc")]
+mod allocator;
+#[cfg(feature = "rkyv")]
+mod archive;
+#[cfg(feature = "async")]
+mod async_buffer;
+#[cfg(feature = "std")]
+mod blocking;
+mod borrowed_buffer;
+#[cfg(feature = "bytes")]
+mod buf;
+#[cfg(feature = "alloc")]
+mod delta;
+#[cfg(feature = "alloc")]
+mod double_buffer;
+#[cfg(feature = "dsp")]
+mod dsp;
+#[cfg(feature = "alloc")]
+mod drain;
+mod error;
+#[cfg(feature = "alloc")]
+mod extract_if;
+#[cfg(feature = "heapless")]
+mod heapless_vec;
+#[cfg(feature = "alloc")]
+mod instrumented;
+#[cfg(feature = "std")]
+mod io;
+mod iter;
+#[cfg(feature = "alloc")]
+mod journal;
+#[cfg(feature = "alloc")]
+mod last;
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "alloc")]
+mod peek_mut;
+mod policy;
+#[cfg(feature = "alloc")]
+mod reduce;
+mod ring;
+mod self_test;
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "alloc")]
+mod shared_window;
+#[cfg(feature = "alloc")]
+mod splice;
+#[cfg(feature = "alloc")]
+mod split_mut;
+#[cfg(feature = "alloc")]
+mod spsc;
+// `f64::round` needs the standard library
+#[cfg(feature = "std")]
+mod sparkline;
+mod static_buffer;
+#[cfg(feature = "tokio")]
+mod tokio_io;
+#[cfg(all(test, feature = "std"))]
+mod tests;
+
+#[cfg(feature = "alloc")]
+pub use allocator::{Allocator, Global};
+#[cfg(feature = "rkyv")]
+pub use archive::ArchivedCircularBuffer;
+#[cfg(feature = "async")]
+pub use async_buffer::AsyncCircularBuffer;
+#[cfg(feature = "std")]
+pub use blocking::BlockingCircularBuffer;
+pub use borrowed_buffer::BorrowedCircularBuffer;
+#[cfg(feature = "alloc")]
+pub use delta::{CumSum, DeltaBuffer, Deltas};
+#[cfg(feature = "alloc")]
+pub use double_buffer::{BackBuffer, DoubleBuffer, FrontBuffer};
+#[cfg(feature = "dsp")]
+pub use dsp::{SpectrumScratch, Window};
+#[cfg(feature = "alloc")]
+pub use drain::Drain;
+pub use error::{AllocError, CapacityError, SelfTestError};
+#[cfg(feature = "alloc")]
+pub use extract_if::ExtractIf;
+#[cfg(feature = "alloc")]
+pub use instrumented::Instrumented;
+pub use iter::{Iter, IterRev, Pairs};
+#[cfg(feature = "alloc")]
+pub use journal::{Entry, JournalSink, Operation, Recorder};
+#[cfg(feature = "alloc")]
+pub use last::{DebugLast, DisplayLast};
+#[cfg(feature = "mmap")]
+pub use mmap::{MmapCircularBuffer, Plain};
+#[cfg(feature = "alloc")]
+pub use peek_mut::PeekMut;
+pub use policy::{Eviction, EvictionPolicy, OldestFirst, OnEvict, Priority, Random, Reject};
+#[cfg(feature = "alloc")]
+pub use reduce::Reduce;
+#[cfg(feature = "alloc")]
+pub use shared_window::SharedWindow;
+#[cfg(feature = "alloc")]
+pub use splice::Splice;
+#[cfg(feature = "alloc")]
+pub use split_mut::{Reader, Writer};
+#[cfg(feature = "alloc")]
+pub use spsc::{Consumer, Producer};
+pub use static_buffer::StaticCircularBuffer;
+
+// used by `circular_buffer!`, the caller may not have `vec!` in scope
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub use alloc::vec as __vec;
+
+#[cfg(feature = "alloc")]
+pub struct CircularBuffer<T, P = OldestFirst, A: Allocator = Global> {
+    // only the slots between the reading and the writing pointer are initialized
+    buffer: NonNull<MaybeUninit<T>>,
+    // writing pointer
+    w: usize,
+    // reading pointer
+    r: usize,
+    size: usize,
+    full: bool,
+    policy: P,
+    // if set, the buffer grows instead of evicting, up to this size
+    growth_limit: Option<usize>,
+    // if set, the array is allocated as it fills up and the capacity is the growth limit
+    lazy: bool,
+    alloc: A,
+}
+
+// The CircularBuffer owns its array and the elements in it, like a `Vec`: the raw pointer is
+// never shared with another CircularBuffer, and every access to the elements goes through `&self`
+// or `&mut self`. Moving it to another thread moves the elements, sharing it shares `&T`.
+#[cfg(feature = "alloc")]
+unsafe impl<T: Send, P: Send, A: Allocator + Send> Send for CircularBuffer<T, P, A> {}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T: Sync, P: Sync, A: Allocator + Sync> Sync for CircularBuffer<T, P, A> {}
+
+#[cfg(feature = "alloc")]
+impl<T> CircularBuffer<T> {
+    /// Create a new CircularBuffer of size `size`.
+    ///
+    /// It allocate an array of exactly size element, if the allocation fail, the method panic.
+    ///
+    /// Negligible amount of space used by the CircularBuffer beside the array itself.
+    ///
+    /// A CircularBuffer of size 0 is valid, it holds nothing and drops every element pushed.
+    pub fn new(size: usize) -> Self {
+        CircularBuffer::with_policy(size, OldestFirst)
+    }
+
+    /// Create a new CircularBuffer of size `size`, returning an error instead of panicking if the
+    /// array cannot be allocated.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let buffer: Result<CircularBuffer<u64>, AllocError> = CircularBuffer::try_new(usize::MAX);
+    /// assert!(buffer.unwrap_err().is_capacity_overflow());
+    /// ```
+    pub fn try_new(size: usize) -> Result<Self, AllocError> {
+        CircularBuffer::try_with_policy(size, OldestFirst)
+    }
+
+    /// Create a new CircularBuffer of size `initial` that doubles its size when it is full,
+    /// instead of overwriting the oldest element.
+    ///
+    /// It allocates at run time, use it when losing elements is worse than allocating.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::growable(2);
+    /// for i in 0..5 {
+    ///     buffer.push(i);
+    /// }
+    /// assert_eq!(5, buffer.len());
+    /// assert_eq!(8, buffer.capacity());
+    /// ```
+    pub fn growable(initial: usize) -> Self {
+        CircularBuffer::growable_up_to(initial, usize::MAX)
+    }
+
+    /// Create a new CircularBuffer of size `initial` that doubles its size when it is full, up to
+    /// `limit` elements. Once the limit is reached, the oldest element is overwritten.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::growable_up_to(2, 3);
+    /// for i in 0..5 {
+    ///     buffer.push(i);
+    /// }
+    /// assert_eq!("CircularBuffer(2, 3, 4)", format!("{}", buffer));
+    /// ```
+    pub fn growable_up_to(initial: usize, limit: usize) -> Self {
+        let mut buffer = CircularBuffer::new(initial);
+        buffer.growth_limit = Some(limit);
+        buffer
+    }
+
+    /// Create a new CircularBuffer of size `size` with the last `size` elements of `iter`.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let buffer = CircularBuffer::from_last(3, 0..10);
+    /// assert_eq!("CircularBuffer(7, 8, 9)", format!("{}", buffer));
+    /// ```
+    pub fn from_last<I: IntoIterator<Item = T>>(size: usize, iter: I) -> Self {
+        let mut buffer = CircularBuffer::new(size);
+        buffer.extend(iter);
+        buffer
+    }
+
+    // A CircularBuffer of capacity `capacity` that allocates an array of `initial` elements, and
+    // doubles it as it fills up, instead of evicting, until it reaches `capacity`.
+    fn try_lazy(capacity: usize, initial: usize) -> Result<Self, AllocError> {
+        if initial >= capacity {
+            return CircularBuffer::try_new(capacity);
+        }
+        let mut buffer = CircularBuffer::try_new(initial)?;
+        buffer.growth_limit = Some(capacity);
+        buffer.lazy = true;
+        Ok(buffer)
+    }
+
+    // Used by the deserializers, where `capacity` comes from the input and may be much larger
+    // than the elements that came with it: the array is allocated up front only if it fits in
+    // `DESERIALIZE_BUDGET` bytes, otherwise it is allocated lazily from `len` elements.
+    #[cfg(any(feature = "serde", feature = "rkyv"))]
+    pub(crate) fn try_for_deserialize(capacity: usize, len: usize) -> Result<Self, AllocError> {
+        const DESERIALIZE_BUDGET: usize = 1024 * 1024;
+        let type_size = core::cmp::max(core::mem::size_of::<T>(), 1);
+        let upfront = core::cmp::max(len, DESERIALIZE_BUDGET / type_size);
+        CircularBuffer::try_lazy(capacity, core::cmp::min(capacity, upfront))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, P> CircularBuffer<T, P> {
+    /// Create a new CircularBuffer of size `size` that uses `policy` to decide which element to
+    /// evict when it is full.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::with_policy(2, Reject);
+    /// buffer.push(1);
+    /// buffer.push(2);
+    /// buffer.push(3);
+    ///
+    /// let v: Vec<u32> = buffer.collect();
+    /// assert_eq!(vec![1, 2], v);
+    /// ```
+    pub fn with_policy(size: usize, policy: P) -> Self {
+        CircularBuffer::with_policy_in(size, policy, Global)
+    }
+
+    fn try_with_policy(size: usize, policy: P) -> Result<Self, AllocError> {
+        CircularBuffer::try_with_policy_in(size, policy, Global)
+    }
+
+    /// Consume the CircularBuffer and return its elements, from the oldest, in a vector that
+    /// reuses its array.
+    ///
+    /// Nothing is allocated: the elements are moved to the start of the array, in place, and the
+    /// vector takes the array over, with a capacity equal to the size of the CircularBuffer.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(4);
+    /// for i in 1..=6 {
+    ///     buffer.push(i);
+    /// }
+    /// buffer.next();
+    /// let v = buffer.into_vec();
+    /// assert_eq!(vec![4, 5, 6], v);
+    /// assert_eq!(4, v.capacity());
+    /// ```
+    pub fn into_vec(mut self) -> Vec<T> {
+        let len = self.len();
+        let start = self.make_contiguous().as_mut_ptr();
+        let ptr = self.as_mut_ptr();
+        unsafe { core::ptr::copy(start, ptr, len) };
+        let capacity = self.size;
+        // the CircularBuffer is left empty and without an array, dropping it only drops the
+        // policy
+        self.buffer = NonNull::dangling();
+        self.size = 0;
+        self.w = 0;
+        self.r = 0;
+        self.full = false;
+        // the array was allocated by the global allocator with the layout of `[T; capacity]`
+        unsafe { Vec::from_raw_parts(ptr, len, capacity) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, A: Allocator> CircularBuffer<T, OldestFirst, A> {
+    /// Create a new CircularBuffer of size `size` whose array is allocated with `alloc`.
+    ///
+    /// Allocators other than `Global` need the nightly `allocator_api` feature.
+    pub fn new_in(size: usize, alloc: A) -> Self {
+        CircularBuffer::with_policy_in(size, OldestFirst, alloc)
+    }
+
+    /// Create a new CircularBuffer of size `size` whose array is allocated with `alloc`, returning
+    /// an error instead of panicking if the array cannot be allocated.
+    pub fn try_new_in(size: usize, alloc: A) -> Result<Self, AllocError> {
+        CircularBuffer::try_with_policy_in(size, OldestFirst, alloc)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, P, A: Allocator> CircularBuffer<T, P, A> {
+    /// Create a new CircularBuffer of size `size` that uses `policy` to decide which element to
+    /// evict when it is full, and whose array is allocated with `alloc`.
+    pub fn with_policy_in(size: usize, policy: P, alloc: A) -> Self {
+        match CircularBuffer::try_with_policy_in(size, policy, alloc) {
+            Ok(buffer) => buffer,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    fn try_with_policy_in(size: usize, policy: P, alloc: A) -> Result<Self, AllocError> {
+        Ok(CircularBuffer {
+            buffer: CircularBuffer::<T, P, A>::allocate(&alloc, size)?,
+            w: 0,
+            r: 0,
+            size,
+            full: false,
+            policy,
+            growth_limit: None,
+            lazy: false,
+            alloc,
+        })
+    }
+
+    /// Returns a reference to the allocator of the array backing the CircularBuffer.
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    fn allocate(alloc: &A, size: usize) -> Result<NonNull<MaybeUninit<T>>, AllocError> {
+        let type_size = core::mem::size_of::<T>();
+        let layout = core::alloc::Layout::array::<T>(size)
+            .map_err(|e| AllocError::capacity_overflow(size, type_size, e))?;
+        // the allocator must not be asked for zero bytes. Zero sized types never allocate: a
+        // dangling pointer is aligned and valid for their reads, writes and offsets, so a buffer
+        // of `()` is only the bookkeeping of its indexes.
+        if layout.size() == 0 {
+            return Ok(NonNull::dangling());
+        }
+        // the slots are left uninitialized, they are written before being read
+        allocator::allocate(alloc, layout)
+            .map(NonNull::cast)
+            .ok_or_else(|| AllocError::out_of_memory(size, type_size))
+    }
+
+    // `ptr` must have been returned by `allocate(alloc, size)`
+    unsafe fn deallocate(alloc: &A, ptr: NonNull<MaybeUninit<T>>, size: usize) {
+        let layout = core::alloc::Layout::array::<T>(size).unwrap();
+        if layout.size() != 0 {
+            allocator::deallocate(alloc, ptr.cast(), layout);
+        }
+    }
+
+    // pointer to the first slot of the array, it must be written before being read
+    fn as_mut_ptr(&self) -> *mut T {
+        self.buffer.as_ptr().cast()
+    }
+
+    /// Change the size of the CircularBuffer to `size`, moving the elements into a new array.
+    ///
+    /// The newest `size` elements are kept, in the same order, the others are dropped. The old
+    /// array is freed.
+    ///
+    /// It allocates, and panics if the allocation fails, the operation runs in O(n) with `n`
+    /// number of elements in the CircularBuffer.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(4);
+    /// for i in 0..4 {
+    ///     buffer.push(i);
+    /// }
+    /// buffer.resize(2);
+    /// assert_eq!("CircularBuffer(2, 3)", format!("{}", buffer));
+    /// buffer.resize(3);
+    /// buffer.push(4);
+    /// assert_eq!("CircularBuffer(2, 3, 4)", format!("{}", buffer));
+    /// ```
+    pub fn resize(&mut self, size: usize) {
+        // the new size is the capacity, an array allocated lazily is not anymore
+        if self.lazy {
+            self.lazy = false;
+            self.growth_limit = None;
+        }
+        self.reallocate(size);
+    }
+
+    fn reallocate(&mut self, size: usize) {
+        if size == self.size {
+            return;
+        }
+        let buffer = match CircularBuffer::<T, P, A>::allocate(&self.alloc, size) {
+            Ok(buffer) => buffer,
+            Err(e) => panic!("{}", e),
+        };
+        self.truncate(size);
+        let len = self.len();
+        let (older, newer) = self.as_raw_slices();
+        let to: *mut T = buffer.as_ptr().cast();
+        unsafe {
+            core::ptr::copy_nonoverlapping(older.as_ptr(), to, older.len());
+            core::ptr::copy_nonoverlapping(newer.as_ptr(), to.add(older.len()), newer.len());
+            CircularBuffer::<T, P, A>::deallocate(&self.alloc, self.buffer, self.size);
+        }
+        self.buffer = buffer;
+        self.size = size;
+        self.r = 0;
+        self.w = 0;
+        self.full = false;
+        if len > 0 {
+            self.set_len(len);
+        }
+    }
+
+    /// Shrink the capacity of the CircularBuffer to the larger of `capacity` and its length,
+    /// keeping all the elements.
+    ///
+    /// It does nothing if the capacity is already smaller than `capacity`, otherwise it
+    /// reallocates in O(n) with `n` number of elements in the CircularBuffer.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(100);
+    /// buffer.extend_from_slice(&[1, 2, 3]);
+    /// buffer.shrink_to(10);
+    /// assert_eq!(10, buffer.capacity());
+    /// buffer.shrink_to(0);
+    /// assert_eq!(3, buffer.capacity());
+    /// assert_eq!("CircularBuffer(1, 2, 3)", format!("{}", buffer));
+    /// ```
+    pub fn shrink_to(&mut self, capacity: usize) {
+        let size = core::cmp::max(capacity, self.len());
+        if size < self.capacity() {
+            self.resize(size);
+        }
+    }
+
+    /// Shrink the capacity of the CircularBuffer as much as possible, keeping all the elements.
+    ///
+    /// It is equivalent to `shrink_to(0)`: an empty CircularBuffer frees its array and, like one
+    /// created with size 0, drops every element pushed until it is resized.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(1_000_000);
+    /// buffer.push(1);
+    /// buffer.next();
+    /// buffer.shrink_to_fit();
+    /// assert_eq!(0, buffer.capacity());
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        self.shrink_to(0);
+    }
+
+    // double the size of a growable buffer, up to its limit
+    fn grow(&mut self) {
+        if let Some(limit) = self.growth_limit {
+            let size = core::cmp::min(core::cmp::max(self.size.saturating_mul(2), 1), limit);
+            if size > self.size {
+                self.reallocate(size);
+            }
+            // fully allocated, it is a plain CircularBuffer from now on
+            if self.lazy && self.size == limit {
+                self.lazy = false;
+                self.growth_limit = None;
+            }
+        }
+    }
+
+    // an empty CircularBuffer with the capacity of this one, allocated lazily if this one is
+    fn empty_like(&self) -> CircularBuffer<T> {
+        let buffer = if self.lazy {
+            CircularBuffer::try_lazy(self.capacity(), self.size)
+        } else {
+            CircularBuffer::try_new(self.size)
+        };
+        match buffer {
+            Ok(buffer) => buffer,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Returns the amount of elements in the CircularBuffer in O(1)
+    pub fn len(&self) -> usize {
+        if self.full {
+            return self.size;
+        }
+        if self.w > self.r {
+            self.w - self.r
+        } else if self.w == self.r {
+            0
+        } else {
+            self.size - self.r + self.w
+        }
+    }
+
+    /// Returns `true` if there are no elements in the CircularBuffer.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements the CircularBuffer can hold before the next `push` evicts an
+    /// element or grows it.
+    ///
+    /// It is the `size` used to create it, a `growable` CircularBuffer starts at `initial` and
+    /// reports the size it has grown to. A deserialized CircularBuffer reports the capacity it was
+    /// serialized with, even while its array is smaller and grows as it fills up.
+    pub fn capacity(&self) -> usize {
+        match self.growth_limit {
+            Some(limit) if self.lazy => limit,
+            _ => self.size,
+        }
+    }
+
+    /// Returns `true` if the CircularBuffer is full, the next `push` will evict an element.
+    pub fn is_full(&self) -> bool {
+        self.full && self.size == self.capacity()
+    }
+
+    /// Returns the number of elements that can be pushed before the CircularBuffer is full.
+    ///
+    /// It is the same value returned by the last `push`.
+    pub fn free_space(&self) -> usize {
+        self.capacity() - self.len()
+    }
+
+    /// Returns the size in bytes of the elements in the CircularBuffer.
+    pub fn len_bytes(&self) -> usize {
+        self.len() * core::mem::size_of::<T>()
+    }
+
+    /// Returns the memory used by the CircularBuffer in bytes: its allocated array plus the
+    /// CircularBuffer itself.
+    ///
+    /// Memory owned by the elements, like the heap part of a `String`, is not counted.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let buffer: CircularBuffer<u64> = CircularBuffer::new(1024);
+    /// assert_eq!(
+    ///     1024 * 8 + std::mem::size_of::<CircularBuffer<u64>>(),
+    ///     buffer.memory_usage()
+    /// );
+    /// ```
+    pub fn memory_usage(&self) -> usize {
+        self.size * core::mem::size_of::<T>() + core::mem::size_of::<Self>()
+    }
+
+    fn next_inc(&self, i: usize) -> usize {
+        (i + 1) % self.size
+    }
+
+    fn w_inc(&mut self) {
+        self.w = self.next_inc(self.w);
+    }
+
+    fn r_inc(&mut self) {
+        self.r = self.next_inc(self.r);
+    }
+
+    fn r_inc_of(&mut self, n: usize) {
+        self.r = (self.r + n) % self.size;
+    }
+
+    // pointer to the element at position `i`, where 0 is the oldest element
+    fn ptr_at(&self, i: usize) -> *mut T {
+        unsafe { self.as_mut_ptr().add((self.r + i) % self.size) }
+    }
+
+    // set the number of elements, keeping the reading pointer where it is
+    fn set_len(&mut self, len: usize) {
+        if self.size == 0 {
+            return;
+        }
+        self.w = (self.r + len) % self.size;
+        self.full = len == self.size;
+    }
+
+    fn write_slot(&mut self, value: T) {
+        let w_index = self.w;
+        self.w_inc();
+        unsafe {
+            self.as_mut_ptr().add(w_index).write(value);
+        }
+    }
+
+    fn read_slot(&mut self) -> T {
+        let r_index = self.r;
+        self.r_inc();
+        unsafe {
+            let ptr = self.as_mut_ptr().add(r_index);
+            ptr.read()
+        }
+    }
+
+    /// Push a new element into the CircularBuffer in O(1), it does not allocate unless the
+    /// CircularBuffer is `growable`.
+    ///
+    /// If the CircularBuffer is full, the first element of the CircularBuffer is overwritten.
+    ///
+    /// A `growable` CircularBuffer is the exception: when it is full and below its limit, `push`
+    /// allocates an array twice as large and moves the elements into it, in O(n), instead of
+    /// overwriting.
+    ///
+    /// With a policy different from `OldestFirst`, the policy decides which element is evicted,
+    /// or if the new element is dropped instead.
+    pub fn push(&mut self, value: T) -> usize
+    where
+        P: EvictionPolicy<T>,
+    {
+        if self.growth_limit.is_some() && self.len() == self.size {
+            self.grow();
+        }
+        // a zero sized buffer cannot hold anything, the value is handed back to the policy
+        if self.size == 0 {
+            self.policy.evicted(value);
+            return 0;
+        }
+        if self.full {
+            let (older, newer) = self.as_raw_slices();
+            match self.policy.evict(older, newer, &value) {
+                Eviction::Oldest => {
+                    // the oldest element is at w, its slot is free once it is read
+                    let oldest = self.read_slot();
+                    self.full = false;
+                    self.policy.evicted(oldest);
+                }
+                Eviction::At(i) => {
+                    let evicted = self.drain(i..=i).next();
+                    if let Some(evicted) = evicted {
+                        self.policy.evicted(evicted);
+                    }
+                }
+                Eviction::Reject => {
+                    self.policy.evicted(value);
+                    return 0;
+                }
+            }
+        }
+        self.write_slot(value);
+        self.full = self.w == self.r;
+        self.free_space()
+    }
+
+    /// Push all the elements of `values` into the CircularBuffer, returns the empty spots left
+    /// like `push`.
+    ///
+    /// With the default `OldestFirst` policy the elements are copied in bulk, using at most two
+    /// memcopy, and if `values` is longer than the CircularBuffer only its last elements are
+    /// copied. With the other policies, and for growable CircularBuffers, the elements are pushed
+    /// one by one. Use `try_extend_from_slice` to fail instead of losing the first elements.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(4);
+    /// buffer.extend_from_slice(&[1, 2, 3]);
+    /// buffer.extend_from_slice(&[4, 5]);
+    /// assert_eq!("CircularBuffer(2, 3, 4, 5)", format!("{}", buffer));
+    /// ```
+    pub fn extend_from_slice(&mut self, values: &[T]) -> usize
+    where
+        T: Copy,
+        P: EvictionPolicy<T>,
+    {
+        if !P::OLDEST_FIRST || self.growth_limit.is_some() {
+            let mut empty_spots = self.free_space();
+            for value in values {
+                empty_spots = self.push(*value);
+            }
+            return empty_spots;
+        }
+        if values.is_empty() || self.size == 0 {
+            return self.free_space();
+        }
+        if values.len() >= self.size {
+            let values = &values[values.len() - self.size..];
+            unsafe {
+                core::ptr::copy_nonoverlapping(values.as_ptr(), self.as_mut_ptr(), self.size);
+            }
+            self.r = 0;
+            self.w = 0;
+            self.full = true;
+            return 0;
+        }
+        let len = self.len();
+        let n = values.len();
+        let first = core::cmp::min(n, self.size - self.w);
+        unsafe {
+            core::ptr::copy_nonoverlapping(values.as_ptr(), self.as_mut_ptr().add(self.w), first);
+            core::ptr::copy_nonoverlapping(values[first..].as_ptr(), self.as_mut_ptr(), n - first);
+        }
+        self.w = (self.w + n) % self.size;
+        if len + n >= self.size {
+            self.r = self.w;
+            self.full = true;
+            0
+        } else {
+            self.size - len - n
+        }
+    }
+
+    /// Push all the elements of `values` into the CircularBuffer like `extend_from_slice`, but
+    /// fails if `values` is longer than the capacity, instead of keeping only its last elements.
+    ///
+    /// On error nothing is pushed. Elements already in the CircularBuffer are still evicted as
+    /// usual to make room for `values`.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(3);
+    /// assert_eq!(Ok(1), buffer.try_extend_from_slice(&[1, 2]));
+    /// let error = buffer.try_extend_from_slice(&[3, 4, 5, 6]).unwrap_err();
+    /// assert_eq!(4, error.requested());
+    /// assert_eq!("CircularBuffer(1, 2)", format!("{}", buffer));
+    /// ```
+    pub fn try_extend_from_slice(&mut self, values: &[T]) -> Result<usize, CapacityError>
+    where
+        T: Copy,
+        P: EvictionPolicy<T>,
+    {
+        let capacity = match self.growth_limit {
+            Some(limit) => limit,
+            None => self.size,
+        };
+        if values.len() > capacity {
+            return Err(CapacityError::new(capacity, values.len()));
+        }
+        Ok(self.extend_from_slice(values))
+    }
+
+    /// Move at most `n` elements, from the oldest, to the back of `dst`, as if they were popped
+    /// and pushed one by one, it returns the number of elements removed from the CircularBuffer.
+    ///
+    /// When `dst` uses the default `OldestFirst` policy the elements are moved in bulk, with a
+    /// few memcopy and no temporary storage. If `dst` is full, its oldest elements are dropped,
+    /// and the elements that would not fit in `dst` anyway are dropped without being copied.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut ingress = CircularBuffer::new(8);
+    /// ingress.extend_from_slice(&[1, 2, 3, 4, 5]);
+    /// let mut worker = CircularBuffer::new(3);
+    /// worker.push(0);
+    /// assert_eq!(3, ingress.move_to(&mut worker, 3));
+    /// assert_eq!(worker, [1, 2, 3]);
+    /// assert_eq!(ingress, [4, 5]);
+    /// ```
+    pub fn move_to<Q, B>(&mut self, dst: &mut CircularBuffer<T, Q, B>, n: usize) -> usize
+    where
+        Q: EvictionPolicy<T>,
+        B: Allocator,
+    {
+        let n = core::cmp::min(n, self.len());
+        if !Q::OLDEST_FIRST || dst.growth_limit.is_some() {
+            for value in self.by_ref().take(n) {
+                dst.push(value);
+            }
+            return n;
+        }
+        // only the last `dst.size` elements would survive in `dst`
+        let dropped = n.saturating_sub(dst.size);
+        self.drop_front(dropped);
+        let to_move = n - dropped;
+        dst.drop_front((dst.len() + to_move).saturating_sub(dst.size));
+        let (older, newer) = self.as_raw_slices();
+        let first = core::cmp::min(to_move, older.len());
+        unsafe {
+            dst.copy_to_back(older.as_ptr(), first);
+            dst.copy_to_back(newer.as_ptr(), to_move - first);
+        }
+        if to_move > 0 {
+            // the elements are owned by `dst` now, only the reading pointer moves
+            self.r_inc_of(to_move);
+            self.full = false;
+        }
+        n
+    }
+
+    /// Move all the elements of `other` to the back of the CircularBuffer, leaving `other`
+    /// empty, returns the empty spots left like `push`.
+    ///
+    /// It is `other.move_to(self, other.len())`: with the default `OldestFirst` policy the
+    /// elements are moved in bulk, the oldest elements are evicted to make room, and the elements
+    /// of `other` that would not fit are dropped without being copied.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut global = CircularBuffer::new(4);
+    /// global.extend_from_slice(&[1, 2]);
+    /// let mut per_thread = CircularBuffer::new(3);
+    /// per_thread.extend_from_slice(&[3, 4, 5]);
+    /// assert_eq!(0, global.append(&mut per_thread));
+    /// assert_eq!(global, [2, 3, 4, 5]);
+    /// assert!(per_thread.is_empty());
+    /// ```
+    pub fn append<Q, B>(&mut self, other: &mut CircularBuffer<T, Q, B>) -> usize
+    where
+        P: EvictionPolicy<T>,
+        B: Allocator,
+    {
+        other.move_to(self, other.len());
+        self.free_space()
+    }
+
+    // copies `n` elements from `src` after the newest one, there must be at least `n` free slots
+    unsafe fn copy_to_back(&mut self, src: *const T, n: usize) {
+        if n == 0 {
+            return;
+        }
+        let len = self.len();
+        let first = core::cmp::min(n, self.size - self.w);
+        core::ptr::copy_nonoverlapping(src, self.as_mut_ptr().add(self.w), first);
+        core::ptr::copy_nonoverlapping(src.add(first), self.as_mut_ptr(), n - first);
+        self.set_len(len + n);
+    }
+
+    /// Remove all the elements from the CircularBuffer, running their destructors.
+    ///
+    /// The allocation is kept, so the CircularBuffer can be reused right away.
+    ///
+    /// The operation runs in O(n) with `n` number of elements in the CircularBuffer, for types
+    /// that do not need to be dropped it runs in O(1).
+    pub fn clear(&mut self) {
+        let (r1, r2) = self.split_in_ranges();
+        // empty the buffer before running the destructors, so if one of them panics the elements
+        // are not dropped again
+        self.r = 0;
+        self.w = 0;
+        self.full = false;
+        unsafe {
+            core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(
+                self.as_mut_ptr().add(r1.start),
+                r1.len(),
+            ));
+            if let Some(r2) = r2 {
+                core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(
+                    self.as_mut_ptr().add(r2.start),
+                    r2.len(),
+                ));
+            }
+        }
+    }
+
+    /// Keep only the newest `n` elements of the CircularBuffer, the oldest ones are dropped.
+    ///
+    /// If the CircularBuffer contains `n` or less elements, nothing happens.
+    ///
+    /// The operation runs in O(m) with `m` number of elements dropped, for types that do not need
+    /// to be dropped it runs in O(1).
+    pub fn truncate(&mut self, n: usize) {
+        let len = self.len();
+        if n >= len {
+            return;
+        }
+        self.drop_front(len - n);
+    }
+
+    /// Discard the `n` oldest elements without reading them, returns the number of elements
+    /// discarded.
+    ///
+    /// If the CircularBuffer contains less than `n` elements, it is emptied.
+    ///
+    /// Only the reading pointer is moved, so for types that do not need to be dropped it runs in
+    /// O(1), otherwise the destructors of the discarded elements are run in O(n).
+    pub fn advance_read(&mut self, n: usize) -> usize {
+        let n = core::cmp::min(n, self.len());
+        self.drop_front(n);
+        n
+    }
+
+    /// Keep only the elements for which `f` returns `true`, the others are dropped.
+    ///
+    /// The order of the retained elements is preserved and the CircularBuffer is compacted in a
+    /// single pass, the operation runs in O(n) with `n` number of elements in the CircularBuffer.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        let len = self.len();
+        // if `f` or a destructor panic, the guard moves back the elements not processed yet
+        let mut guard = RetainGuard {
+            buffer: self,
+            processed: 0,
+            deleted: 0,
+            len,
+        };
+        while guard.processed < len {
+            let current = guard.buffer.ptr_at(guard.processed);
+            if !f(unsafe { &*current }) {
+                guard.processed += 1;
+                guard.deleted += 1;
+                unsafe { core::ptr::drop_in_place(current) };
+                continue;
+            }
+            if guard.deleted > 0 {
+                unsafe {
+                    let hole = guard.buffer.ptr_at(guard.processed - guard.deleted);
+                    core::ptr::copy_nonoverlapping(current, hole, 1);
+                }
+            }
+            guard.processed += 1;
+        }
+    }
+
+    /// Removes the consecutive equal elements, only the oldest of each run is kept.
+    ///
+    /// Like `retain`, the CircularBuffer is compacted in a single pass.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut readings = CircularBuffer::new(8);
+    /// readings.extend_from_slice(&[20, 20, 21, 21, 21, 20, 22, 22]);
+    /// readings.dedup();
+    /// assert_eq!(readings, [20, 21, 20, 22]);
+    /// ```
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b)
+    }
+
+    /// Removes the consecutive elements that map to the same key, only the oldest of each run is
+    /// kept.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut readings = CircularBuffer::new(4);
+    /// readings.extend_from_slice(&[10, 14, 21, 35]);
+    /// readings.dedup_by_key(|r| *r / 10);
+    /// assert_eq!(readings, [10, 21, 35]);
+    /// ```
+    pub fn dedup_by_key<K: PartialEq, F: FnMut(&mut T) -> K>(&mut self, mut key: F) {
+        self.dedup_by(|a, b| key(a) == key(b))
+    }
+
+    /// Removes the consecutive elements for which `same_bucket` returns `true`, like
+    /// `Vec::dedup_by`.
+    ///
+    /// `same_bucket` is called with each element and the last element kept before it, older,
+    /// and if it returns `true` the element is removed.
+    pub fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, mut same_bucket: F) {
+        let len = self.len();
+        if len <= 1 {
+            return;
+        }
+        // the oldest element is always kept, the guard works as in `retain`
+        let mut guard = RetainGuard {
+            buffer: self,
+            processed: 1,
+            deleted: 0,
+            len,
+        };
+        while guard.processed < len {
+            let current = guard.buffer.ptr_at(guard.processed);
+            let kept = guard.buffer.ptr_at(guard.processed - guard.deleted - 1);
+            if same_bucket(unsafe { &mut *current }, unsafe { &mut *kept }) {
+                guard.processed += 1;
+                guard.deleted += 1;
+                unsafe { core::ptr::drop_in_place(current) };
+                continue;
+            }
+            if guard.deleted > 0 {
+                unsafe {
+                    let hole = guard.buffer.ptr_at(guard.processed - guard.deleted);
+                    core::ptr::copy_nonoverlapping(current, hole, 1);
+                }
+            }
+            guard.processed += 1;
+        }
+    }
+
+    /// Returns a mutable reference to the oldest element, or `None` if the CircularBuffer is empty.
+    ///
+    /// The element can be inspected, modified and, with `PeekMut::pop`, removed from the
+    /// CircularBuffer, all with a single borrow.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(3);
+    /// buffer.push(1);
+    /// buffer.push(2);
+    ///
+    /// if let Some(mut oldest) = buffer.peek_mut() {
+    ///     *oldest += 10;
+    /// }
+    /// let oldest = buffer.peek_mut().unwrap();
+    /// if *oldest > 10 {
+    ///     assert_eq!(11, PeekMut::pop(oldest));
+    /// }
+    /// assert_eq!("CircularBuffer(2)", format!("{}", buffer));
+    /// ```
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, P, A>> {
+        PeekMut::new(self)
+    }
+
+    /// Returns an iterator over references to the elements, from the oldest to the newest,
+    /// without consuming them.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(2);
+    /// buffer.extend_from_slice(&[1, 2, 3]);
+    /// let v: Vec<&u32> = buffer.iter().collect();
+    /// assert_eq!(vec![&2, &3], v);
+    /// assert_eq!(2, buffer.len());
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self)
+    }
+
+    /// Returns an iterator over references to the elements, from the newest to the oldest,
+    /// without consuming them.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(2);
+    /// buffer.extend_from_slice(&[1, 2, 3]);
+    /// let v: Vec<&u32> = buffer.iter_rev().collect();
+    /// assert_eq!(vec![&3, &2], v);
+    /// ```
+    pub fn iter_rev(&self) -> IterRev<'_, T> {
+        IterRev::new(self.iter())
+    }
+
+    /// Returns the elements as two slices, the older elements first, without consuming them.
+    ///
+    /// The elements are in the order of the first slice followed by the second one, which is
+    /// empty unless the elements wrap around the end of the array.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(3);
+    /// buffer.extend_from_slice(&[1, 2, 3]);
+    /// assert_eq!((&[1, 2, 3][..], &[][..]), buffer.as_slices());
+    /// buffer.push(4);
+    /// assert_eq!((&[2, 3][..], &[4][..]), buffer.as_slices());
+    /// ```
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        self.as_raw_slices()
+    }
+
+    /// Call `f` with the two slices of `as_slices`, the older elements first, and return its
+    /// result.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(3);
+    /// buffer.extend_from_slice(&[1, 2, 3]);
+    /// buffer.push(4);
+    /// let max = buffer.with_slices(|older, newer| older.iter().chain(newer).max().copied());
+    /// assert_eq!(Some(4), max);
+    /// ```
+    pub fn with_slices<R, F: FnOnce(&[T], &[T]) -> R>(&self, f: F) -> R {
+        let (older, newer) = self.as_slices();
+        f(older, newer)
+    }
+
+    /// Returns a vector with a clone of the elements, from the oldest, without consuming them.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(3);
+    /// buffer.extend_from_slice(&[1, 2, 3]);
+    /// buffer.push(4);
+    /// assert_eq!(vec![2, 3, 4], buffer.to_vec());
+    /// assert_eq!(3, buffer.len());
+    /// ```
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let (older, newer) = self.as_slices();
+        let mut vec = Vec::with_capacity(self.len());
+        vec.extend_from_slice(older);
+        vec.extend_from_slice(newer);
+        vec
+    }
+
+    /// Returns a copy of the CircularBuffer, like `clone`, copying the elements with at most two
+    /// memcopy instead of cloning them one by one.
+    ///
+    /// The elements stay in the same slots, so the copy wraps around where the original does.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut samples = CircularBuffer::new(1024);
+    /// for i in 0..1500 {
+    ///     samples.push(i as f32);
+    /// }
+    /// let snapshot = samples.duplicate_copy();
+    /// assert_eq!(snapshot, samples);
+    /// assert_eq!(samples.as_slices(), snapshot.as_slices());
+    /// ```
+    pub fn duplicate_copy(&self) -> Self
+    where
+        T: Copy,
+        P: Clone,
+        A: Clone,
+    {
+        let mut new: CircularBuffer<T, P, A> =
+            CircularBuffer::with_policy_in(self.size, self.policy.clone(), self.alloc.clone());
+        new.growth_limit = self.growth_limit;
+        new.lazy = self.lazy;
+        let (r1, r2) = self.split_in_ranges();
+        for range in core::iter::once(r1).chain(r2) {
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    self.as_mut_ptr().add(range.start),
+                    new.as_mut_ptr().add(range.start),
+                    range.len(),
+                );
+            }
+        }
+        new.w = self.w;
+        new.r = self.r;
+        new.full = self.full;
+        new
+    }
+
+    /// Returns the elements as two mutable slices, the older elements first, without consuming
+    /// them.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(3);
+    /// for i in 1..=4 {
+    ///     buffer.push(i);
+    /// }
+    /// let (older, newer) = buffer.as_mut_slices();
+    /// older[0] = 20;
+    /// newer[0] = 40;
+    /// assert_eq!("CircularBuffer(20, 3, 40)", format!("{}", buffer));
+    /// ```
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (r1, r2) = self.split_in_ranges();
+        let r2 = r2.unwrap_or(0..0);
+        // the two ranges never overlap
+        unsafe {
+            (
+                core::slice::from_raw_parts_mut(self.as_mut_ptr().add(r1.start), r1.len()),
+                core::slice::from_raw_parts_mut(self.as_mut_ptr().add(r2.start), r2.len()),
+            )
+        }
+    }
+
+    /// Move the elements so they are all in a single slice, from the oldest, and return it.
+    ///
+    /// It does not allocate. If the elements already are in one slice nothing is moved,
+    /// otherwise they are rotated in place in O(n). The elements keep their order, so the
+    /// CircularBuffer is used as before.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(4);
+    /// for i in 1..=6 {
+    ///     buffer.push(i);
+    /// }
+    /// assert_eq!((&[3, 4][..], &[5, 6][..]), buffer.as_slices());
+    /// assert_eq!(&mut [3, 4, 5, 6], buffer.make_contiguous());
+    /// assert_eq!((&[3, 4, 5, 6][..], &[][..]), buffer.as_slices());
+    /// ```
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        let len = self.len();
+        match self.split_in_ranges() {
+            (older, Some(newer)) if !newer.is_empty() => unsafe {
+                let ptr = self.as_mut_ptr();
+                if !self.full {
+                    // the older elements join the newer ones in the free slots, then the two are
+                    // swapped
+                    core::ptr::copy(ptr.add(older.start), ptr.add(newer.end), older.len());
+                }
+                core::slice::from_raw_parts_mut(ptr, len).rotate_left(newer.len());
+                self.r = 0;
+                self.w = len % self.size;
+            },
+            _ => {}
+        }
+        let r = self.r;
+        unsafe { core::slice::from_raw_parts_mut(self.as_mut_ptr().add(r), len) }
+    }
+
+    /// Rotates the elements so the `n` oldest ones become the newest, like
+    /// `VecDeque::rotate_left`.
+    ///
+    /// When the CircularBuffer is full only the pointers move, in O(1). Otherwise
+    /// `min(n, len - n)` elements are moved. It panics if `n` is greater than `len`.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut schedule = circular_buffer!["a", "b", "c", "d"];
+    /// schedule.rotate_left(1);
+    /// assert_eq!(schedule, ["b", "c", "d", "a"]);
+    /// schedule.rotate_right(3);
+    /// assert_eq!(schedule, ["c", "d", "a", "b"]);
+    /// ```
+    pub fn rotate_left(&mut self, n: usize) {
+        let len = self.len();
+        assert!(n <= len, "rotating by {} a CircularBuffer of {} elements", n, len);
+        if self.full {
+            self.r = (self.r + n) % self.size;
+            self.w = self.r;
+        } else if n <= len - n {
+            for _ in 0..n {
+                let value = self.read_slot();
+                self.write_slot(value);
+            }
+        } else {
+            self.rotate_back_to_front(len - n);
+        }
+    }
+
+    /// Rotates the elements so the `n` newest ones become the oldest, like
+    /// `VecDeque::rotate_right`.
+    ///
+    /// It runs like `rotate_left`, and panics if `n` is greater than `len`.
+    pub fn rotate_right(&mut self, n: usize) {
+        let len = self.len();
+        assert!(n <= len, "rotating by {} a CircularBuffer of {} elements", n, len);
+        if self.full {
+            self.r = (self.r + self.size - n) % self.size;
+            self.w = self.r;
+        } else if n <= len - n {
+            self.rotate_back_to_front(n);
+        } else {
+            for _ in 0..len - n {
+                let value = self.read_slot();
+                self.write_slot(value);
+            }
+        }
+    }
+
+    // moves the `n` newest elements, one by one, before the oldest, the buffer must not be full
+    fn rotate_back_to_front(&mut self, n: usize) {
+        for _ in 0..n {
+            self.w = (self.w + self.size - 1) % self.size;
+            self.r = (self.r + self.size - 1) % self.size;
+            unsafe {
+                let ptr = self.as_mut_ptr();
+                core::ptr::copy_nonoverlapping(ptr.add(self.w), ptr.add(self.r), 1);
+            }
+        }
+    }
+
+    /// Sorts the elements in place, the smallest becomes the oldest, like
+    /// `slice::sort_unstable`.
+    ///
+    /// The elements are first made contiguous with `make_contiguous`, nothing is allocated.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(4);
+    /// buffer.extend_from_slice(&[9, 3, 7, 1, 5]);
+    /// buffer.sort_unstable();
+    /// assert_eq!(buffer, [1, 3, 5, 7]);
+    /// ```
+    pub fn sort_unstable(&mut self)
+    where
+        T: Ord,
+    {
+        self.make_contiguous().sort_unstable();
+    }
+
+    /// Sorts the elements in place with a comparator function, like `slice::sort_unstable_by`.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(3);
+    /// buffer.extend_from_slice(&[2, 8, 5]);
+    /// buffer.sort_unstable_by(|a, b| b.cmp(a));
+    /// assert_eq!(buffer, [8, 5, 2]);
+    /// ```
+    pub fn sort_unstable_by<F>(&mut self, compare: F)
+    where
+        F: FnMut(&T, &T) -> core::cmp::Ordering,
+    {
+        self.make_contiguous().sort_unstable_by(compare);
+    }
+
+    /// Sorts the elements in place with a key extraction function, like
+    /// `slice::sort_unstable_by_key`.
+    pub fn sort_unstable_by_key<K: Ord, F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> K,
+    {
+        self.make_contiguous().sort_unstable_by_key(f);
+    }
+
+    /// Returns an adaptor that formats with `Display` only the newest `k` elements.
+    ///
+    /// If older elements are left out, the list starts with `..`. Nothing is copied, so it can be
+    /// used to add the recent context to an error message without dumping the whole
+    /// CircularBuffer.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let buffer = CircularBuffer::from_last(100, 0..1000);
+    /// assert_eq!("CircularBuffer(.., 997, 998, 999)", format!("{}", buffer.display_last(3)));
+    /// ```
+    pub fn display_last(&self, k: usize) -> DisplayLast<'_, T> {
+        DisplayLast::new(self.iter(), k)
+    }
+
+    /// Returns an adaptor that formats with `Debug` only the newest `k` elements, like
+    /// `display_last`.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(4);
+    /// buffer.extend_from_slice(&["GET /", "GET /a", "POST /b"]);
+    /// assert_eq!(
+    ///     r#"CircularBuffer(.., "GET /a", "POST /b")"#,
+    ///     format!("{:?}", buffer.debug_last(2))
+    /// );
+    /// ```
+    pub fn debug_last(&self, k: usize) -> DebugLast<'_, T> {
+        DebugLast::new(self.iter(), k)
+    }
+
+    /// Returns a reference to the oldest element, or `None` if the CircularBuffer is empty.
+    pub fn front(&self) -> Option<&T> {
+        if self.is_empty() {
+            return None;
+        }
+        unsafe { Some(&*self.ptr_at(0)) }
+    }
+
+    /// Returns a reference to the newest element, or `None` if the CircularBuffer is empty.
+    pub fn back(&self) -> Option<&T> {
+        if self.is_empty() {
+            return None;
+        }
+        unsafe { Some(&*self.ptr_at(self.len() - 1)) }
+    }
+
+    /// Swaps the elements at positions `i` and `j`, where 0 is the oldest element.
+    ///
+    /// It panics if either position is not less than `len`.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(3);
+    /// buffer.extend_from_slice(&[1, 2, 3, 4]);
+    /// buffer.swap(0, 2);
+    /// assert_eq!(buffer, [4, 3, 2]);
+    /// ```
+    pub fn swap(&mut self, i: usize, j: usize) {
+        let len = self.len();
+        assert!(
+            i < len && j < len,
+            "swapping {} and {} in a CircularBuffer of {} elements",
+            i,
+            j,
+            len
+        );
+        unsafe { core::ptr::swap(self.ptr_at(i), self.ptr_at(j)) };
+    }
+
+    /// Replaces the element at position `index`, where 0 is the oldest element, with `value` and
+    /// returns the element replaced.
+    ///
+    /// It panics if `index` is not less than `len`.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut history = CircularBuffer::new(3);
+    /// history.extend_from_slice(&[1, 2, 3, 4]);
+    /// assert_eq!(3, history.replace(1, 30));
+    /// assert_eq!(history, [2, 30, 4]);
+    /// ```
+    pub fn replace(&mut self, index: usize, value: T) -> T {
+        let len = self.len();
+        assert!(
+            index < len,
+            "replacing the element {} in a CircularBuffer of {} elements",
+            index,
+            len
+        );
+        unsafe { core::mem::replace(&mut *self.ptr_at(index), value) }
+    }
+
+    /// Inserts `value` at position `index`, where 0 is the oldest element, moving the elements
+    /// on the shorter side of `index` by one. Returns the empty spots left like `push`.
+    ///
+    /// If the CircularBuffer is full, the policy first evicts an element, as for `push`, and the
+    /// positions after the evicted element shift down by one: with `OldestFirst` the new element
+    /// ends up after the `index - 1` elements that preceded it. If the policy rejects `value`,
+    /// nothing is inserted. Growable CircularBuffers grow instead.
+    ///
+    /// It panics if `index` is greater than `len`.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut working_set = CircularBuffer::new(4);
+    /// working_set.extend_from_slice(&[10, 20, 40]);
+    /// assert_eq!(0, working_set.insert(2, 30));
+    /// assert_eq!(working_set, [10, 20, 30, 40]);
+    ///
+    /// working_set.insert(2, 25);
+    /// assert_eq!(working_set, [20, 25, 30, 40]);
+    /// ```
+    pub fn insert(&mut self, index: usize, value: T) -> usize
+    where
+        P: EvictionPolicy<T>,
+    {
+        let len = self.len();
+        assert!(
+            index <= len,
+            "inserting at {} in a CircularBuffer of {} elements",
+            index,
+            len
+        );
+        if self.growth_limit.is_some() && len == self.size {
+            self.grow();
+        }
+        if self.size == 0 {
+            self.policy.evicted(value);
+            return 0;
+        }
+        let mut index = index;
+        if self.full {
+            let (older, newer) = self.as_raw_slices();
+            match self.policy.evict(older, newer, &value) {
+                Eviction::Oldest => {
+                    let oldest = self.read_slot();
+                    self.full = false;
+                    self.policy.evicted(oldest);
+                    index = index.saturating_sub(1);
+                }
+                Eviction::At(i) => {
+                    let evicted = self.drain(i..=i).next();
+                    if let Some(evicted) = evicted {
+                        self.policy.evicted(evicted);
+                    }
+                    if i < index {
+                        index -= 1;
+                    }
+                }
+                Eviction::Reject => {
+                    self.policy.evicted(value);
+                    return 0;
+                }
+            }
+        }
+        let len = self.len();
+        if index < len - index {
+            self.r = (self.r + self.size - 1) % self.size;
+            for i in 0..index {
+                unsafe { core::ptr::copy_nonoverlapping(self.ptr_at(i + 1), self.ptr_at(i), 1) };
+            }
+        } else {
+            for i in (index..len).rev() {
+                unsafe { core::ptr::copy_nonoverlapping(self.ptr_at(i), self.ptr_at(i + 1), 1) };
+            }
+        }
+        unsafe { self.ptr_at(index).write(value) };
+        self.set_len(len + 1);
+        self.free_space()
+    }
+
+    /// Removes and returns the element at position `index`, where 0 is the oldest element, or
+    /// `None` if `index` is out of bounds.
+    ///
+    /// The elements on the shorter side of `index` are moved to close the gap.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut working_set = CircularBuffer::new(3);
+    /// working_set.extend_from_slice(&[1, 2, 3, 4]);
+    /// assert_eq!(Some(3), working_set.remove(1));
+    /// assert_eq!(None, working_set.remove(2));
+    /// assert_eq!(working_set, [2, 4]);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.len() {
+            return None;
+        }
+        self.drain(index..=index).next()
+    }
 
-use std::convert::TryInto;
+    /// Removes and returns the element at position `index` in O(1), replacing it with the oldest
+    /// element, or returns `None` if `index` is out of bounds.
+    ///
+    /// The order of the elements left is not preserved, use `remove` to keep it.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut pending = CircularBuffer::new(4);
+    /// pending.extend_from_slice(&[1, 2, 3, 4]);
+    /// assert_eq!(Some(3), pending.swap_remove_front(2));
+    /// assert_eq!(pending, [2, 1, 4]);
+    /// ```
+    pub fn swap_remove_front(&mut self, index: usize) -> Option<T> {
+        if index >= self.len() {
+            return None;
+        }
+        if index != 0 {
+            self.swap(index, 0);
+        }
+        self.next()
+    }
 
-#[cfg(test)]
-mod tests;
+    /// Removes and returns the element at position `index` in O(1), replacing it with the newest
+    /// element, or returns `None` if `index` is out of bounds.
+    ///
+    /// The order of the elements left is not preserved, use `remove` to keep it.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut pending = CircularBuffer::new(4);
+    /// pending.extend_from_slice(&[1, 2, 3, 4]);
+    /// assert_eq!(Some(2), pending.swap_remove_back(1));
+    /// assert_eq!(pending, [1, 4, 3]);
+    /// ```
+    pub fn swap_remove_back(&mut self, index: usize) -> Option<T> {
+        let len = self.len();
+        if index >= len {
+            return None;
+        }
+        if index != len - 1 {
+            self.swap(index, len - 1);
+        }
+        let element = unsafe { self.ptr_at(len - 1).read() };
+        self.set_len(len - 1);
+        Some(element)
+    }
 
-#[derive(Copy)]
-pub struct CircularBuffer<T> {
-    buffer: *mut T,
-    // writing pointer
-    w: usize,
-    // reading pointer
-    r: usize,
-    size: usize,
-    full: bool,
-}
+    /// Returns `true` if the CircularBuffer holds an element equal to `x`.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut recent_ids = CircularBuffer::new(2);
+    /// recent_ids.extend_from_slice(&[7, 8, 9]);
+    /// assert!(recent_ids.contains(&9));
+    /// assert!(!recent_ids.contains(&7));
+    /// ```
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        let (older, newer) = self.as_slices();
+        older.contains(x) || newer.contains(x)
+    }
 
-impl<T> CircularBuffer<T> {
-    /// Create a new CircularBuffer of size `size`.
+    /// Returns the position of the oldest element for which `pred` returns `true`, where 0 is
+    /// the oldest element, or `None` if there is none.
     ///
-    /// It allocate an array of exactly size element, if the allocation fail, the method panic.
+    /// Unlike `Iterator::position` no element is consumed.
     ///
-    /// Negligible amount of space used by the CircularBuffer beside the array itself.
-    pub fn new(size: usize) -> Self {
-        let size = size;
-        let type_size = std::mem::size_of::<T>();
-        let vector_size = type_size.checked_mul(size).unwrap();
-        let aligment = std::mem::align_of::<T>();
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(3);
+    /// buffer.extend_from_slice(&[1, 2, 3, 4]);
+    /// assert_eq!(Some(1), buffer.position(|e| e % 2 == 1));
+    /// assert_eq!(None, buffer.position(|e| *e > 4));
+    /// assert_eq!(3, buffer.len());
+    /// ```
+    pub fn position<F: FnMut(&T) -> bool>(&self, pred: F) -> Option<usize> {
+        self.iter().position(pred)
+    }
 
-        let layout = std::alloc::Layout::from_size_align(vector_size, aligment).unwrap();
-        let ptr = unsafe { std::alloc::alloc_zeroed(layout) };
+    /// Returns a reference to the oldest element for which `pred` returns `true`, or `None` if
+    /// there is none.
+    ///
+    /// Unlike `Iterator::find` no element is consumed.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(3);
+    /// buffer.extend_from_slice(&[1, 2, 3, 4]);
+    /// assert_eq!(Some(&3), buffer.find(|e| e % 2 == 1));
+    /// assert_eq!(3, buffer.len());
+    /// ```
+    pub fn find<F: FnMut(&T) -> bool>(&self, mut pred: F) -> Option<&T> {
+        self.iter().find(|e| pred(e))
+    }
 
-        CircularBuffer {
-            buffer: ptr.cast(),
-            w: 0,
-            r: 0,
-            size,
-            full: false,
-        }
+    /// Binary searches the sorted CircularBuffer for `x`, like `slice::binary_search`.
+    ///
+    /// The elements must be sorted from the oldest to the newest. It returns `Ok` with the
+    /// position of a matching element, where 0 is the oldest, or `Err` with the position where
+    /// `x` could be inserted keeping the order. The wrap-around is handled internally, it runs in
+    /// O(log n).
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut timestamps = CircularBuffer::new(4);
+    /// timestamps.extend_from_slice(&[10, 20, 30, 40, 50]);
+    /// assert_eq!(Ok(2), timestamps.binary_search(&40));
+    /// assert_eq!(Err(1), timestamps.binary_search(&25));
+    /// ```
+    pub fn binary_search(&self, x: &T) -> Result<usize, usize>
+    where
+        T: Ord,
+    {
+        self.binary_search_by(|e| e.cmp(x))
     }
 
-    /// Returns the amount of elements in the CircularBuffer in O(1)
-    pub fn len(&self) -> usize {
-        if self.full {
-            return self.size;
+    /// Binary searches the sorted CircularBuffer with a comparator function, like
+    /// `slice::binary_search_by`.
+    ///
+    /// `f` returns the order of the element compared with the one searched, the positions are
+    /// like in `binary_search`.
+    pub fn binary_search_by<F>(&self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> core::cmp::Ordering,
+    {
+        let (older, newer) = self.as_slices();
+        match newer.first().map(&mut f) {
+            Some(core::cmp::Ordering::Equal) => Ok(older.len()),
+            Some(core::cmp::Ordering::Less) => newer
+                .binary_search_by(f)
+                .map(|i| i + older.len())
+                .map_err(|i| i + older.len()),
+            _ => older.binary_search_by(f),
         }
-        if self.w > self.r {
-            self.w - self.r
-        } else if self.w == self.r {
-            0
-        } else {
-            self.size - self.r + self.w
+    }
+
+    /// Binary searches the sorted CircularBuffer with a key extraction function, like
+    /// `slice::binary_search_by_key`.
+    ///
+    /// The elements must be sorted by the key, the positions are like in `binary_search`.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut samples = CircularBuffer::new(3);
+    /// samples.extend_from_slice(&[(1, 'a'), (2, 'b'), (3, 'c'), (4, 'd')]);
+    /// assert_eq!(Ok(1), samples.binary_search_by_key(&3, |&(t, _)| t));
+    /// assert_eq!(Err(0), samples.binary_search_by_key(&1, |&(t, _)| t));
+    /// ```
+    pub fn binary_search_by_key<K: Ord, F>(&self, key: &K, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> K,
+    {
+        self.binary_search_by(|e| f(e).cmp(key))
+    }
+
+    /// Returns the position of the first element for which `pred` returns `false`, where 0 is
+    /// the oldest element, like `slice::partition_point`.
+    ///
+    /// The CircularBuffer must be partitioned: `pred` returns `true` for the older elements and
+    /// `false` for the newer ones. It runs in O(log n).
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut timestamps = CircularBuffer::new(4);
+    /// timestamps.extend_from_slice(&[10, 20, 20, 30, 40]);
+    /// // the first element newer than 20
+    /// assert_eq!(2, timestamps.partition_point(|t| *t <= 20));
+    /// ```
+    pub fn partition_point<F: FnMut(&T) -> bool>(&self, mut pred: F) -> usize {
+        let (older, newer) = self.as_slices();
+        match newer.first() {
+            Some(first) if pred(first) => older.len() + newer.partition_point(pred),
+            _ => older.partition_point(pred),
         }
     }
 
-    fn next_inc(&self, i: usize) -> usize {
-        (i + 1) % self.size
+    /// Push a new element into the CircularBuffer, like `push`.
+    ///
+    /// Together with `pop_front`, `front`, `back` and `iter` it mirrors the `VecDeque` API, so
+    /// that code can switch between the two with minimal changes.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(2);
+    /// buffer.push_back(1);
+    /// buffer.push_back(2);
+    /// buffer.push_back(3);
+    /// assert_eq!(Some(&2), buffer.front());
+    /// assert_eq!(Some(&3), buffer.back());
+    /// assert_eq!(Some(2), buffer.pop_front());
+    /// ```
+    pub fn push_back(&mut self, value: T)
+    where
+        P: EvictionPolicy<T>,
+    {
+        self.push(value);
     }
 
-    fn w_inc(&mut self) {
-        self.w = self.next_inc(self.w);
+    /// Remove the oldest element and return it, or `None` if the CircularBuffer is empty.
+    ///
+    /// It is the same as `next`.
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.next()
     }
 
-    fn r_inc(&mut self) {
-        self.r = self.next_inc(self.r);
+    /// Returns an iterator over the pairs of consecutive elements, from the oldest, without
+    /// consuming them.
+    ///
+    /// A CircularBuffer of `n` elements yields `n - 1` pairs.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(4);
+    /// for i in [1, 2, 4, 8, 16] {
+    ///     buffer.push(i);
+    /// }
+    /// let deltas: Vec<_> = buffer.iter_pairs().map(|(a, b)| b - a).collect();
+    /// assert_eq!(vec![2, 4, 8], deltas);
+    /// assert_eq!(4, buffer.len());
+    /// ```
+    pub fn iter_pairs(&self) -> Pairs<'_, T> {
+        Pairs::new(self)
     }
 
-    fn r_inc_of(&mut self, n: usize) {
-        self.r = (self.r + n) % self.size;
+    /// Remove the elements in `range` from the CircularBuffer and return them in an iterator.
+    ///
+    /// The range is over the logical positions of the elements, 0 is the oldest element.
+    ///
+    /// The elements are removed even if the iterator is not fully consumed, when the iterator is
+    /// dropped the remaining elements are dropped as well. The elements around the drained range
+    /// are then moved next to each other, moving the shorter side.
+    ///
+    /// Panics if the start of the range is greater than its end or if the end is greater than the
+    /// length of the CircularBuffer.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(5);
+    /// for i in 0..5 {
+    ///     buffer.push(i);
+    /// }
+    /// let middle: Vec<_> = buffer.drain(1..4).collect();
+    /// assert_eq!(vec![1, 2, 3], middle);
+    /// assert_eq!("CircularBuffer(0, 4)", format!("{}", buffer));
+    /// ```
+    pub fn drain<R: core::ops::RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, T, P, A> {
+        use core::ops::Bound;
+        let len = self.len();
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.checked_add(1).expect("range start overflow"),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n.checked_add(1).expect("range end overflow"),
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => len,
+        };
+        assert!(start <= end, "drain start {} is greater than end {}", start, end);
+        assert!(end <= len, "drain end {} is greater than len {}", end, len);
+        Drain::new(self, start, end)
     }
 
-    fn write(&mut self, value: T) {
-        let w_index = self.w;
-        self.w_inc();
-        unsafe {
-            self.buffer.add(w_index).write(value);
-        }
+    /// Returns an iterator that removes and yields the elements for which `pred` returns `true`.
+    ///
+    /// The elements are examined lazily, in order from the oldest, and the ones not extracted stay
+    /// in the CircularBuffer in the same order. Dropping the iterator early leaves the elements not
+    /// examined yet in the CircularBuffer.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(5);
+    /// for i in 0..5 {
+    ///     buffer.push(i);
+    /// }
+    /// let odds: Vec<_> = buffer.extract_if(|i| *i % 2 == 1).collect();
+    /// assert_eq!(vec![1, 3], odds);
+    /// assert_eq!("CircularBuffer(0, 2, 4)", format!("{}", buffer));
+    /// ```
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, T, P, F, A>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        ExtractIf::new(self, pred)
     }
 
-    fn read(&mut self) -> T {
-        let r_index = self.r;
-        self.r_inc();
-        unsafe {
-            let ptr = self.buffer.add(r_index);
-            ptr.read()
+    /// Split the elements in two CircularBuffers, in one pass from the oldest: the first one holds
+    /// the elements for which `pred` returns `true`, the second one the others.
+    ///
+    /// Both CircularBuffers have the capacity of this one, so no element is evicted, and the
+    /// elements keep their order.
+    ///
+    /// It takes the place of `Iterator::partition`, that would collect into two zero sized
+    /// CircularBuffers: call `Iterator::partition` explicitly to collect into other collections.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut events = CircularBuffer::new(4);
+    /// events.extend_from_slice(&[3, 12, 5, 20, 1]);
+    /// let (urgent, deferred) = events.partition(|priority| *priority > 10);
+    /// assert_eq!(urgent, [12, 20]);
+    /// assert_eq!(deferred, [5, 1]);
+    /// assert_eq!(4, urgent.capacity());
+    /// ```
+    pub fn partition<F>(mut self, mut pred: F) -> (CircularBuffer<T>, CircularBuffer<T>)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut matching = self.empty_like();
+        let mut rest = self.empty_like();
+        for element in &mut self {
+            if pred(&element) {
+                matching.push(element);
+            } else {
+                rest.push(element);
+            }
         }
+        (matching, rest)
     }
 
-    fn drop(&mut self) {
+    /// Split the CircularBuffer in two at position `at`, where 0 is the oldest element: the
+    /// returned CircularBuffer holds the newest `len - at` elements, and this one keeps the
+    /// oldest `at`.
+    ///
+    /// The returned CircularBuffer has the capacity of this one, the elements are moved with at
+    /// most two memcopy.
+    ///
+    /// It panics if `at` is greater than `len`.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut backlog = CircularBuffer::new(4);
+    /// backlog.extend_from_slice(&[1, 2, 3, 4, 5]);
+    /// let half = backlog.len() / 2;
+    /// let next_stage = backlog.split_off(half);
+    /// assert_eq!(backlog, [2, 3]);
+    /// assert_eq!(next_stage, [4, 5]);
+    /// assert_eq!(4, next_stage.capacity());
+    /// ```
+    pub fn split_off(&mut self, at: usize) -> CircularBuffer<T> {
+        let len = self.len();
+        assert!(
+            at <= len,
+            "splitting off at {} a CircularBuffer of {} elements",
+            at,
+            len
+        );
+        let mut other = self.empty_like();
+        let (older, newer) = self.as_raw_slices();
         unsafe {
-            let ptr = self.buffer.offset(self.w.try_into().unwrap());
-            std::ptr::drop_in_place(ptr);
+            if at < older.len() {
+                other.copy_to_back(older.as_ptr().add(at), older.len() - at);
+                other.copy_to_back(newer.as_ptr(), newer.len());
+            } else {
+                other.copy_to_back(newer.as_ptr().add(at - older.len()), len - at);
+            }
         }
+        // the elements after `at` are owned by `other` now
+        self.set_len(at);
+        other
     }
 
-    /// Push a new element into the CircularBuffer in O(1) does not do any allocation.
-    ///
-    /// If the CircularBuffer is full, the first element of the CircularBuffer is overwritten.
-    pub fn push(&mut self, value: T) -> usize {
-        if self.full {
-            // pointer to w must first be free, and the overwritten
-            self.drop();
-            self.r_inc();
+    // drops the `n` oldest elements, `n` must not be greater than `len`
+    fn drop_front(&mut self, n: usize) {
+        if n == 0 {
+            return;
         }
-        self.write(value);
-        if self.w == self.r {
-            self.full = true;
-            0
-        } else {
-            self.size - self.len()
+        let first = core::cmp::min(n, self.size - self.r);
+        let (first_part, second_part) = unsafe {
+            (
+                core::ptr::slice_from_raw_parts_mut(self.as_mut_ptr().add(self.r), first),
+                core::ptr::slice_from_raw_parts_mut(self.as_mut_ptr(), n - first),
+            )
+        };
+        // move the reading pointer before running the destructors, so the buffer is consistent
+        // even if one of them panics
+        self.r_inc_of(n);
+        self.full = false;
+        unsafe {
+            core::ptr::drop_in_place(first_part);
+            core::ptr::drop_in_place(second_part);
         }
     }
 
@@ -233,6 +2088,9 @@ impl<T> CircularBuffer<T> {
     /// while the CircularBuffer will be left with some elements inside.
     ///
     /// The operation runs in O(n) with `n` number of elements pushed into the vector.
+    ///
+    /// To move the elements into any other collection, a `VecDeque` or a `BinaryHeap` for
+    /// instance, use `drain_into`, it takes any `Extend` sink and a maximum number of elements.
     pub fn fill(&mut self, return_vector: &mut Vec<T>) -> usize {
         let mut i = 0;
         while return_vector.capacity() - return_vector.len() > 0 {
@@ -247,7 +2105,164 @@ impl<T> CircularBuffer<T> {
         i
     }
 
-    fn split_in_ranges(&self) -> (std::ops::Range<usize>, Option<std::ops::Range<usize>>) {
+    /// Moves at most `n` elements, from the oldest, to the back of `vec`, it returns the number of
+    /// elements moved.
+    ///
+    /// Unlike `fill`, the number of elements does not depend on the spare capacity of the vector:
+    /// the vector grows if needed, with a single allocation.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(4);
+    /// buffer.extend_from_slice(&[1, 2, 3, 4]);
+    /// let mut v = vec![];
+    /// assert_eq!(3, buffer.fill_n(&mut v, 3));
+    /// assert_eq!(vec![1, 2, 3], v);
+    /// assert_eq!(1, buffer.fill_n(&mut v, 3));
+    /// assert_eq!(vec![1, 2, 3, 4], v);
+    /// ```
+    pub fn fill_n(&mut self, vec: &mut Vec<T>, n: usize) -> usize {
+        let n = core::cmp::min(n, self.len());
+        vec.reserve(n);
+        vec.extend(self.by_ref().take(n));
+        n
+    }
+
+    /// Moves the oldest elements to the back of `vec` as long as `pred` returns `true`, it returns
+    /// the number of elements moved.
+    ///
+    /// It stops at the first element for which `pred` returns `false`, that element and the newer
+    /// ones are left in the CircularBuffer. The vector grows if needed.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut timestamps = CircularBuffer::new(8);
+    /// timestamps.extend_from_slice(&[10, 12, 17, 21, 15]);
+    /// let mut frame = Vec::new();
+    /// assert_eq!(3, timestamps.fill_while(&mut frame, |t| *t < 20));
+    /// assert_eq!(vec![10, 12, 17], frame);
+    /// assert_eq!(timestamps, [21, 15]);
+    /// ```
+    pub fn fill_while<F: FnMut(&T) -> bool>(&mut self, vec: &mut Vec<T>, mut pred: F) -> usize {
+        let mut i = 0;
+        while self.front().is_some_and(&mut pred) {
+            vec.extend(self.next());
+            i += 1;
+        }
+        i
+    }
+
+    /// Moves the oldest elements into `out`, from its start, it returns the number of elements
+    /// moved.
+    ///
+    /// At most `out.len()` elements are moved, the values they replace are dropped and the rest of
+    /// `out` is left untouched. Like `fill` it never allocates, and it does not need a `Vec`.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(4);
+    /// buffer.extend_from_slice(&[1, 2, 3, 4, 5]);
+    /// let mut out = [0; 3];
+    /// assert_eq!(3, buffer.fill_slice(&mut out));
+    /// assert_eq!([2, 3, 4], out);
+    /// assert_eq!(1, buffer.fill_slice(&mut out));
+    /// assert_eq!([5, 3, 4], out);
+    /// ```
+    pub fn fill_slice(&mut self, out: &mut [T]) -> usize {
+        let mut i = 0;
+        for (slot, element) in out.iter_mut().zip(self.by_ref()) {
+            *slot = element;
+            i += 1;
+        }
+        i
+    }
+
+    // moves the oldest elements into `sink`, as many as fit, returns the number of elements moved
+    #[cfg(feature = "std")]
+    fn read_into(&mut self, sink: &mut [T]) -> usize
+    where
+        T: Copy,
+    {
+        let n = self.copy_to_slice(sink);
+        // `T: Copy` has no destructor, only the reading pointer moves
+        self.drop_front(n);
+        n
+    }
+
+    /// Copies the oldest elements into `out`, from its start, without removing them, it returns
+    /// the number of elements copied.
+    ///
+    /// At most `out.len()` elements are copied, with at most two memcopy, the rest of `out` is
+    /// left untouched.
+    ///
+    /// With the `bytes` feature a `CircularBuffer<u8>` is also a `bytes::Buf`, whose
+    /// `copy_to_slice` consumes the bytes: call it as `Buf::copy_to_slice(&mut buffer, out)`.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(4);
+    /// buffer.extend_from_slice(&[1, 2, 3, 4, 5]);
+    /// let mut snapshot = [0; 8];
+    /// assert_eq!(4, buffer.copy_to_slice(&mut snapshot));
+    /// assert_eq!([2, 3, 4, 5], snapshot[..4]);
+    /// assert_eq!(4, buffer.len());
+    /// ```
+    pub fn copy_to_slice(&self, out: &mut [T]) -> usize
+    where
+        T: Copy,
+    {
+        let (older, newer) = self.as_slices();
+        let first = core::cmp::min(out.len(), older.len());
+        out[..first].copy_from_slice(&older[..first]);
+        let second = core::cmp::min(out.len() - first, newer.len());
+        out[first..first + second].copy_from_slice(&newer[..second]);
+        first + second
+    }
+
+    // the elements as two slices, the lifetime is not bound to self, so the slices must not
+    // outlive any change to the buffer
+    fn as_raw_slices<'a>(&self) -> (&'a [T], &'a [T]) {
+        let (r1, r2) = self.split_in_ranges();
+        let r2 = r2.unwrap_or(0..0);
+        unsafe {
+            (
+                core::slice::from_raw_parts(self.as_mut_ptr().add(r1.start), r1.len()),
+                core::slice::from_raw_parts(self.as_mut_ptr().add(r2.start), r2.len()),
+            )
+        }
+    }
+
+    /// Move up to `limit` elements, from the oldest, into `sink`, returns the number of elements
+    /// moved.
+    ///
+    /// Unlike `fill`, the number of elements moved does not depend on the capacity of the
+    /// destination, so any collection that implements `Extend` can be used, and the destination
+    /// may allocate.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    /// use std::collections::VecDeque;
+    ///
+    /// let mut buffer = CircularBuffer::new(5);
+    /// for i in 0..5 {
+    ///     buffer.push(i);
+    /// }
+    /// let mut sink = VecDeque::new();
+    /// assert_eq!(3, buffer.drain_into(&mut sink, 3));
+    /// assert_eq!(vec![0, 1, 2], Vec::from(sink));
+    /// assert_eq!(2, buffer.len());
+    /// ```
+    pub fn drain_into<E: Extend<T>>(&mut self, sink: &mut E, limit: usize) -> usize {
+        let n = core::cmp::min(limit, self.len());
+        sink.extend(self.drain(..n));
+        n
+    }
+
+    fn split_in_ranges(&self) -> (core::ops::Range<usize>, Option<core::ops::Range<usize>>) {
         if self.r < self.w {
             (self.r..self.w, None)
         } else if self.r == self.w {
@@ -261,12 +2276,12 @@ impl<T> CircularBuffer<T> {
         }
     }
 
-    fn fill_vector_from_split(&mut self, range: std::ops::Range<usize>, vec: &mut Vec<T>) -> usize {
+    fn fill_vector_from_split(&mut self, range: core::ops::Range<usize>, vec: &mut Vec<T>) -> usize {
         let sink_capacity = vec.capacity() - vec.len();
         if sink_capacity == 0 {
             return 0;
         }
-        if range.len() == 0 {
+        if range.is_empty() {
             return 0;
         }
         let to_push = if range.len() <= sink_capacity {
@@ -279,13 +2294,13 @@ impl<T> CircularBuffer<T> {
 
         unsafe {
             let ptr = vec.as_mut_ptr().add(vec.len());
-            std::ptr::copy_nonoverlapping(self.buffer.add(to_push.start), ptr, to_push.len());
+            core::ptr::copy_nonoverlapping(self.as_mut_ptr().add(to_push.start), ptr, to_push.len());
             vec.set_len(vec.len() + to_push.len());
         }
 
         self.r_inc_of(to_push.len());
         self.full = false;
-        return to_push.len();
+        to_push.len()
     }
 
     /// The `_fast_fill` method is supposed to be a faster alternative to the `fill` one.
@@ -299,7 +2314,7 @@ impl<T> CircularBuffer<T> {
     /// The `_fast_fill` is implemented using raw pointer and memcopy. While the `fill` method
     /// pull elements using the iterator and simply push them to the back of the vector.
     pub fn _fast_fill(&mut self, return_vector: &mut Vec<T>) -> usize {
-        if self.len() == 0 {
+        if self.is_empty() {
             return 0;
         }
         let sink_capacity = return_vector.capacity() - return_vector.len();
@@ -319,47 +2334,272 @@ impl<T> CircularBuffer<T> {
     }
 }
 
-impl<T: Clone> Clone for CircularBuffer<T> {
+#[cfg(feature = "alloc")]
+struct RetainGuard<'a, T, P, A: Allocator> {
+    buffer: &'a mut CircularBuffer<T, P, A>,
+    processed: usize,
+    deleted: usize,
+    len: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, P, A: Allocator> Drop for RetainGuard<'a, T, P, A> {
+    fn drop(&mut self) {
+        if self.deleted > 0 {
+            for i in self.processed..self.len {
+                unsafe {
+                    let from = self.buffer.ptr_at(i);
+                    let to = self.buffer.ptr_at(i - self.deleted);
+                    core::ptr::copy_nonoverlapping(from, to, 1);
+                }
+            }
+        }
+        self.buffer.set_len(self.len - self.deleted);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, P, A: Allocator> Drop for CircularBuffer<T, P, A> {
+    fn drop(&mut self) {
+        self.clear();
+        unsafe {
+            CircularBuffer::<T, P, A>::deallocate(&self.alloc, self.buffer, self.size);
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Cloning a CircularBuffer makes a deep copy: a new allocation of the same capacity holding a
+/// clone of every element, in the same positions, from a clone of the allocator.
+///
+/// The two CircularBuffers are independent, pushing to or draining one does not affect the other.
+impl<T: Clone, P: Clone, A: Allocator + Clone> Clone for CircularBuffer<T, P, A> {
     fn clone(&self) -> Self {
-        let mut new = CircularBuffer::new(self.size);
-        new.w = self.w;
+        let mut new: CircularBuffer<T, P, A> =
+            CircularBuffer::with_policy_in(self.size, self.policy.clone(), self.alloc.clone());
+        new.growth_limit = self.growth_limit;
+        new.lazy = self.lazy;
+
+        // the elements are cloned in the same slots, each one is part of `new` as soon as it is
+        // written, so if `T::clone` panics the elements already cloned are dropped with `new`
         new.r = self.r;
-        new.size = self.size; // useless
+        new.w = self.r;
+        for element in self.iter() {
+            new.write_slot(element.clone());
+        }
         new.full = self.full;
 
-        let (r1, r2) = self.split_in_ranges();
-        for i in r1 {
-            unsafe {
-                let r_ptr = self.buffer.add(i);
-                let e0 = r_ptr.read();
-                let e1 = e0.clone();
-                std::mem::forget(e0);
-                let w_buffer = new.buffer as *mut T;
-                let w_ptr = w_buffer.add(i);
-                w_ptr.write(e1);
-            }
+        new
+    }
+
+    /// Reuses the allocation when the two CircularBuffers have the same size, otherwise it is
+    /// the same as `clone`.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut frame = CircularBuffer::new(3);
+    /// let mut snapshot = CircularBuffer::new(3);
+    /// for i in 0..5 {
+    ///     frame.push(i);
+    ///     snapshot.clone_from(&frame);
+    /// }
+    /// assert_eq!(snapshot, [2, 3, 4]);
+    /// ```
+    fn clone_from(&mut self, source: &Self) {
+        if self.size != source.size {
+            *self = source.clone();
+            return;
         }
-        if let Some(r2) = r2 {
-            for i in r2 {
-                unsafe {
-                    let r_ptr = self.buffer.add(i);
-                    let e0 = r_ptr.read();
-                    let e1 = e0.clone();
-                    std::mem::forget(e0);
-                    let w_buffer = new.buffer as *mut T;
-                    let w_ptr = w_buffer.add(i);
-                    w_ptr.write(e1);
+        self.clear();
+        self.policy.clone_from(&source.policy);
+        self.growth_limit = source.growth_limit;
+        self.lazy = source.lazy;
+        // the elements are cloned in the same slots of `source`, each one is part of the buffer
+        // as soon as it is written, so if `T::clone` panics nothing is leaked
+        self.r = source.r;
+        self.w = source.r;
+        for element in source.iter() {
+            self.write_slot(element.clone());
+        }
+        self.full = source.full;
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// An empty CircularBuffer of size 0, it does not allocate.
+///
+/// It holds nothing and drops every element pushed until it is given a size with `resize`, so it
+/// is mostly a placeholder, for `#[derive(Default)]` and `core::mem::take`.
+///
+/// ```
+/// use rbl_circular_buffer::*;
+///
+/// let mut buffer = circular_buffer![1, 2, 3];
+/// let taken = std::mem::take(&mut buffer);
+/// assert_eq!(taken, [1, 2, 3]);
+/// assert_eq!(0, buffer.capacity());
+/// assert!(buffer.is_empty());
+/// ```
+impl<T, P: Default, A: Allocator + Default> Default for CircularBuffer<T, P, A> {
+    fn default() -> Self {
+        CircularBuffer::with_policy_in(0, P::default(), A::default())
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Two CircularBuffers are equal if they hold equal elements in the same order, from the oldest.
+///
+/// Where the elements are stored does not matter, nor do the capacity, the policy and the
+/// allocator.
+///
+/// A CircularBuffer is also an `Iterator`, so `a.eq(&b)` and `a.ne(&b)` call the consuming
+/// `Iterator::eq` and `Iterator::ne`, that empty both buffers: use `==` and `!=`, or
+/// `PartialEq::eq(&a, &b)`, instead.
+///
+/// ```
+/// use rbl_circular_buffer::*;
+///
+/// let mut a = CircularBuffer::new(3);
+/// a.extend_from_slice(&[1, 2, 3, 4]);
+/// let mut b = CircularBuffer::new(5);
+/// b.extend_from_slice(&[2, 3, 4]);
+/// assert_eq!(a, b);
+/// b.push(5);
+/// assert_ne!(a, b);
+/// ```
+impl<T, U, P, Q, A, B> PartialEq<CircularBuffer<U, Q, B>> for CircularBuffer<T, P, A>
+where
+    T: PartialEq<U>,
+    A: Allocator,
+    B: Allocator,
+{
+    fn eq(&self, other: &CircularBuffer<U, Q, B>) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Eq, P, A: Allocator> Eq for CircularBuffer<T, P, A> {}
+
+#[cfg(feature = "alloc")]
+/// CircularBuffers are compared lexicographically, from the oldest element, like slices.
+///
+/// A CircularBuffer is also an `Iterator`, so `a.partial_cmp(&b)`, `a.lt(&b)`, `a.le(&b)`,
+/// `a.gt(&b)` and `a.ge(&b)` call the consuming `Iterator` methods, that empty both buffers: use
+/// the operators, or `PartialOrd::partial_cmp(&a, &b)`, instead.
+///
+/// ```
+/// use rbl_circular_buffer::*;
+///
+/// let mut a = CircularBuffer::new(3);
+/// a.extend_from_slice(&[1, 2, 3, 4]);
+/// assert!(a > circular_buffer![2, 3]);
+/// assert!(a < circular_buffer![2, 4]);
+/// ```
+impl<T, P, Q, A, B> PartialOrd<CircularBuffer<T, Q, B>> for CircularBuffer<T, P, A>
+where
+    T: PartialOrd,
+    A: Allocator,
+    B: Allocator,
+{
+    fn partial_cmp(&self, other: &CircularBuffer<T, Q, B>) -> Option<core::cmp::Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// CircularBuffers are ordered lexicographically, from the oldest element, like slices.
+///
+/// A CircularBuffer is also an `Iterator`, so `a.cmp(&b)`, `a.max(b)` and `a.min(b)` call the
+/// consuming `Iterator` methods: use `Ord::cmp(&a, &b)` instead.
+impl<T: Ord, P, A: Allocator> Ord for CircularBuffer<T, P, A> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, P, A: Allocator> CircularBuffer<T, P, A> {
+    // compares the two halves with the two parts of the slice, without copying
+    fn eq_slice<U>(&self, other: &[U]) -> bool
+    where
+        T: PartialEq<U>,
+    {
+        let (first, second) = self.as_raw_slices();
+        first.len() + second.len() == other.len()
+            && first == &other[..first.len()]
+            && second == &other[first.len()..]
+    }
+}
+
+// A CircularBuffer is equal to a sequence holding equal elements in the same order, from the
+// oldest, like two CircularBuffers.
+macro_rules! impl_eq_sequence {
+    ($([$($generics:tt)*] $rhs:ty),* $(,)?) => {
+        $(
+            #[cfg(feature = "alloc")]
+            impl<$($generics)* T, U, P, A: Allocator> PartialEq<$rhs> for CircularBuffer<T, P, A>
+            where
+                T: PartialEq<U>,
+            {
+                fn eq(&self, other: &$rhs) -> bool {
+                    self.eq_slice(&other[..])
                 }
             }
+        )*
+    };
+}
+
+impl_eq_sequence! {
+    [] [U],
+    ['a,] &'a [U],
+    ['a,] &'a mut [U],
+    [] Vec<U>,
+    [const N: usize,] [U; N],
+    ['a, const N: usize,] &'a [U; N],
+}
+
+#[cfg(feature = "alloc")]
+/// Hash the length and the elements, from the oldest, so equal CircularBuffers have the same
+/// hash.
+///
+/// ```
+/// use rbl_circular_buffer::*;
+/// use std::collections::HashMap;
+///
+/// let mut window = CircularBuffer::new(2);
+/// let mut cache = HashMap::new();
+/// for input in [1, 2, 3, 1, 2] {
+///     window.push(input);
+///     *cache.entry(window.clone()).or_insert(0) += 1;
+/// }
+/// assert_eq!(Some(&2), cache.get(&CircularBuffer::from(vec![1, 2])));
+/// ```
+impl<T: core::hash::Hash, P, A: Allocator> core::hash::Hash for CircularBuffer<T, P, A> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        for element in self.iter() {
+            element.hash(state);
         }
+    }
+}
 
-        new
+#[cfg(feature = "alloc")]
+/// Borrow the elements, from the oldest, without consuming them, like `iter`.
+impl<'a, T, P, A: Allocator> IntoIterator for &'a CircularBuffer<T, P, A> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
     }
 }
 
+#[cfg(feature = "alloc")]
 /// Create an iterator, elements from the iterator are consumed and are not present anymore in the
 /// buffer.
-impl<T> std::iter::Iterator for CircularBuffer<T> {
+impl<T, P, A: Allocator> core::iter::Iterator for CircularBuffer<T, P, A> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -367,7 +2607,7 @@ impl<T> std::iter::Iterator for CircularBuffer<T> {
             0 => None,
             _ => {
                 self.full = false;
-                Some(self.read())
+                Some(self.read_slot())
             }
         }
     }
@@ -377,31 +2617,128 @@ impl<T> std::iter::Iterator for CircularBuffer<T> {
     }
 }
 
-impl<T: std::fmt::Debug> std::fmt::Debug for CircularBuffer<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.len() == 0 {
+#[cfg(feature = "alloc")]
+/// Create a CircularBuffer with the elements of the vector, the size of the CircularBuffer is the
+/// length of the vector, so the CircularBuffer starts full.
+impl<T> From<Vec<T>> for CircularBuffer<T> {
+    fn from(mut vec: Vec<T>) -> Self {
+        let len = vec.len();
+        let mut buffer = CircularBuffer::new(len);
+        unsafe {
+            vec.set_len(0);
+            core::ptr::copy_nonoverlapping(vec.as_ptr(), buffer.as_mut_ptr(), len);
+        }
+        if len > 0 {
+            buffer.set_len(len);
+        }
+        buffer
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Create a CircularBuffer with the elements of the array, the size of the CircularBuffer is the
+/// length of the array, so the CircularBuffer starts full.
+impl<T, const N: usize> From<[T; N]> for CircularBuffer<T> {
+    fn from(array: [T; N]) -> Self {
+        CircularBuffer::from(Vec::from(array))
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Create a CircularBuffer with the elements of the deque, from the front, the size of the
+/// CircularBuffer is the length of the deque, so the CircularBuffer starts full.
+impl<T> From<alloc::collections::VecDeque<T>> for CircularBuffer<T> {
+    fn from(deque: alloc::collections::VecDeque<T>) -> Self {
+        CircularBuffer::from(Vec::from(deque))
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Create a deque with the elements of the CircularBuffer, the oldest at the front.
+///
+/// Like `into_vec`, it reuses the array of the CircularBuffer, so the capacity of the deque is
+/// the size of the CircularBuffer.
+///
+/// ```
+/// use rbl_circular_buffer::*;
+/// use std::collections::VecDeque;
+///
+/// let mut buffer = CircularBuffer::new(3);
+/// buffer.extend_from_slice(&[1, 2, 3, 4]);
+/// let mut deque = VecDeque::from(buffer);
+/// assert_eq!(Some(2), deque.pop_front());
+/// assert_eq!(CircularBuffer::from(deque), [3, 4]);
+/// ```
+impl<T, P> From<CircularBuffer<T, P>> for alloc::collections::VecDeque<T> {
+    fn from(buffer: CircularBuffer<T, P>) -> Self {
+        alloc::collections::VecDeque::from(buffer.into_vec())
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Create a CircularBuffer with a clone of the elements of the slice, the size of the
+/// CircularBuffer is the length of the slice, so the CircularBuffer starts full.
+///
+/// It fails if the array backing the CircularBuffer cannot be allocated.
+impl<T: Clone> core::convert::TryFrom<&[T]> for CircularBuffer<T> {
+    type Error = AllocError;
+
+    fn try_from(slice: &[T]) -> Result<Self, Self::Error> {
+        let mut buffer = CircularBuffer::try_with_policy(slice.len(), OldestFirst)?;
+        for value in slice {
+            buffer.push(value.clone());
+        }
+        Ok(buffer)
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Create a CircularBuffer with all the elements of the iterator, the size of the CircularBuffer
+/// is the number of elements.
+///
+/// Use `CircularBuffer::from_last` to keep only the last elements of a long iterator.
+impl<T> core::iter::FromIterator<T> for CircularBuffer<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let vec: Vec<T> = iter.into_iter().collect();
+        CircularBuffer::from(vec)
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Push all the elements of the iterator, with the same semantics of `push`.
+impl<T, P: EvictionPolicy<T>, A: Allocator> Extend<T> for CircularBuffer<T, P, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: Copy + 'a, P: EvictionPolicy<T>, A: Allocator> Extend<&'a T>
+    for CircularBuffer<T, P, A>
+{
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(*value);
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: core::fmt::Debug, P, A: Allocator> core::fmt::Debug for CircularBuffer<T, P, A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.is_empty() {
             return write!(f, "CircularBuffer(<empty>)");
         }
         write!(f, "CircularBuffer(")?;
-        let mut fake_read = self.r;
-        let read = fake_read.try_into().unwrap();
-        let element = unsafe {
-            let ptr = self.buffer.offset(read);
-            ptr.read()
-        };
-        std::fmt::Debug::fmt(&element, f)?;
-        std::mem::forget(element);
-        fake_read = self.next_inc(fake_read);
-        while fake_read != self.w {
-            write!(f, ", ")?;
-            let read = fake_read.try_into().unwrap();
-            let element = unsafe {
-                let ptr = self.buffer.offset(read);
-                ptr.read()
-            };
-            std::fmt::Debug::fmt(&element, f)?;
-            std::mem::forget(element);
-            fake_read = self.next_inc(fake_read);
+        // the elements are only borrowed, an error or a panic while formatting them cannot drop
+        // them
+        for (i, element) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            core::fmt::Debug::fmt(element, f)?;
         }
         write!(
             f,
@@ -411,31 +2748,20 @@ impl<T: std::fmt::Debug> std::fmt::Debug for CircularBuffer<T> {
     }
 }
 
-impl<T: std::fmt::Display> std::fmt::Display for CircularBuffer<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.len() == 0 {
+#[cfg(feature = "alloc")]
+impl<T: core::fmt::Display, P, A: Allocator> core::fmt::Display for CircularBuffer<T, P, A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.is_empty() {
             return write!(f, "CircularBuffer(<empty>)");
         }
         write!(f, "CircularBuffer(")?;
-        let mut fake_read = self.r;
-        let read = fake_read.try_into().unwrap();
-        let element = unsafe {
-            let ptr = self.buffer.offset(read);
-            ptr.read()
-        };
-        std::fmt::Display::fmt(&element, f)?;
-        std::mem::forget(element);
-        fake_read = self.next_inc(fake_read);
-        while fake_read != self.w {
-            write!(f, ", ")?;
-            let read = fake_read.try_into().unwrap();
-            let element = unsafe {
-                let ptr = self.buffer.offset(read);
-                ptr.read()
-            };
-            std::fmt::Display::fmt(&element, f)?;
-            std::mem::forget(element);
-            fake_read = self.next_inc(fake_read);
+        // the elements are only borrowed, an error or a panic while formatting them cannot drop
+        // them
+        for (i, element) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            core::fmt::Display::fmt(element, f)?;
         }
         write!(f, ")")
     }
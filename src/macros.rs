@@ -0,0 +1,28 @@
+/// Create a CircularBuffer containing the arguments, analogous to `vec!`.
+///
+/// `circular_buffer![a, b, c]` creates a CircularBuffer of size 3 with the three elements, while
+/// `circular_buffer![x; n]` creates a CircularBuffer of size `n` filled with clones of `x`. In both
+/// cases the size of the CircularBuffer is the number of elements, so it starts full.
+///
+/// ```
+/// use rbl_circular_buffer::*;
+///
+/// let mut buffer = circular_buffer![1, 2, 3];
+/// buffer.push(4);
+/// assert_eq!("CircularBuffer(2, 3, 4)", format!("{}", buffer));
+///
+/// let zeros = circular_buffer![0; 64];
+/// assert_eq!(64, zeros.len());
+/// ```
+#[macro_export]
+macro_rules! circular_buffer {
+    () => {
+        $crate::CircularBuffer::new(0)
+    };
+    ($elem:expr; $n:expr) => {
+        $crate::CircularBuffer::from($crate::__vec![$elem; $n])
+    };
+    ($($x:expr),+ $(,)?) => {
+        $crate::CircularBuffer::from([$($x),+])
+    };
+}
@@ -0,0 +1,250 @@
+use std::fs::OpenOptions;
+use std::io;
+use std::path::Path;
+
+use memmap2::MmapMut;
+
+use crate::ring::fmt_elements;
+use crate::Iter;
+
+const MAGIC: [u8; 8] = *b"RBLRING\0";
+const VERSION: u32 = 1;
+// the elements start after the header, at an offset aligned for any `Plain` type
+const DATA_OFFSET: usize = 64;
+
+/// Types that can be stored in a file and read back, any bit pattern is a valid value.
+///
+/// # Safety
+///
+/// The type must have no padding, no pointers and no invalid bit patterns: integers, floats and
+/// arrays of them are fine, `bool`, `char` and references are not.
+pub unsafe trait Plain: Copy + 'static {}
+
+macro_rules! impl_plain {
+    ($($t:ty),*) => {
+        $(unsafe impl Plain for $t {})*
+    };
+}
+
+impl_plain!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+unsafe impl<T: Plain, const N: usize> Plain for [T; N] {}
+
+// stored at the start of the file, the indexes are updated after the elements they describe are
+// written, so a crash loses at most the element being pushed
+#[repr(C)]
+struct Header {
+    magic: [u8; 8],
+    version: u32,
+    element_size: u32,
+    capacity: u64,
+    // reading pointer
+    r: u64,
+    len: u64,
+}
+
+/// A circular buffer stored in a memory-mapped file, its elements survive the process.
+///
+/// The file starts with a header holding the capacity, the size of the elements and the reading
+/// pointer, then the slots. Opening the same file again gives back the same elements, in the
+/// same order. Like a `CircularBuffer` with the default `OldestFirst` policy, when it is full
+/// `push` overwrites the oldest element.
+///
+/// The elements must be `Plain`, the file is read back without any validation of its content.
+///
+/// ```
+/// use rbl_circular_buffer::*;
+///
+/// let path = std::env::temp_dir().join("rbl_circular_buffer_doc.ring");
+/// # let _ = std::fs::remove_file(&path);
+/// {
+///     let mut buffer = MmapCircularBuffer::<u64>::open_mmap(&path, 3).unwrap();
+///     for i in 0..5 {
+///         buffer.push(i);
+///     }
+/// }
+/// let buffer = MmapCircularBuffer::<u64>::open_mmap(&path, 3).unwrap();
+/// assert_eq!("MmapCircularBuffer(2, 3, 4)", format!("{}", buffer));
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct MmapCircularBuffer<T: Plain> {
+    map: MmapMut,
+    size: usize,
+    _marker: core::marker::PhantomData<T>,
+}
+
+impl<T: Plain> MmapCircularBuffer<T> {
+    /// Open the buffer stored in the file at `path`, creating it with room for `capacity`
+    /// elements if it does not exist.
+    ///
+    /// An existing file must have been created with the same `capacity` and elements of the same
+    /// size, otherwise it fails with `io::ErrorKind::InvalidData` and the file is not modified.
+    pub fn open_mmap<Q: AsRef<Path>>(path: Q, capacity: usize) -> io::Result<Self> {
+        assert!(core::mem::align_of::<T>() <= DATA_OFFSET, "elements aligned to more than 64 bytes");
+        let bytes = capacity
+            .checked_mul(core::mem::size_of::<T>())
+            .and_then(|bytes| bytes.checked_add(DATA_OFFSET))
+            .ok_or_else(|| invalid_data("capacity overflow"))?;
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let created = file.metadata()?.len() == 0;
+        if created {
+            file.set_len(bytes as u64)?;
+        }
+        let map = unsafe { MmapMut::map_mut(&file)? };
+        let mut buffer = MmapCircularBuffer {
+            map,
+            size: capacity,
+            _marker: core::marker::PhantomData,
+        };
+        if created {
+            *buffer.header_mut() = Header {
+                magic: MAGIC,
+                version: VERSION,
+                element_size: core::mem::size_of::<T>() as u32,
+                capacity: capacity as u64,
+                r: 0,
+                len: 0,
+            };
+            buffer.map.flush_range(0, DATA_OFFSET)?;
+        } else {
+            buffer.check_header(bytes)?;
+        }
+        Ok(buffer)
+    }
+
+    fn check_header(&self, bytes: usize) -> io::Result<()> {
+        if self.map.len() < DATA_OFFSET {
+            return Err(invalid_data("file too short for a header"));
+        }
+        let header = self.header();
+        if header.magic != MAGIC || header.version != VERSION {
+            return Err(invalid_data("not a ring buffer file"));
+        }
+        if header.element_size as usize != core::mem::size_of::<T>()
+            || header.capacity != self.size as u64
+            || self.map.len() != bytes
+        {
+            return Err(invalid_data("ring buffer file of a different capacity or element size"));
+        }
+        if header.r >= header.capacity.max(1) || header.len > header.capacity {
+            return Err(invalid_data("corrupted ring buffer indexes"));
+        }
+        Ok(())
+    }
+
+    fn header(&self) -> &Header {
+        // the map is page aligned and longer than the header
+        unsafe { &*self.map.as_ptr().cast::<Header>() }
+    }
+
+    fn header_mut(&mut self) -> &mut Header {
+        unsafe { &mut *self.map.as_mut_ptr().cast::<Header>() }
+    }
+
+    fn slots(&mut self) -> *mut T {
+        unsafe { self.map.as_mut_ptr().add(DATA_OFFSET).cast() }
+    }
+
+    /// Returns the amount of elements in the MmapCircularBuffer in O(1)
+    pub fn len(&self) -> usize {
+        self.header().len as usize
+    }
+
+    /// Returns `true` if there are no elements in the MmapCircularBuffer.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements the MmapCircularBuffer can hold.
+    pub fn capacity(&self) -> usize {
+        self.size
+    }
+
+    /// Push a new element into the MmapCircularBuffer in O(1), returns the empty spots left.
+    ///
+    /// If the MmapCircularBuffer is full, the oldest element is overwritten. The element reaches
+    /// the file when the operating system writes back the page, call `flush` to wait for it.
+    pub fn push(&mut self, value: T) -> usize {
+        // a zero sized buffer cannot hold anything, the value is dropped
+        if self.size == 0 {
+            return 0;
+        }
+        let size = self.size as u64;
+        if self.header().len == size {
+            // forget the oldest element before its slot is overwritten
+            let header = self.header_mut();
+            header.r = (header.r + 1) % size;
+            header.len -= 1;
+        }
+        let header = self.header();
+        let w = ((header.r + header.len) % size) as usize;
+        unsafe { self.slots().add(w).write(value) };
+        self.header_mut().len += 1;
+        self.size - self.len()
+    }
+
+    /// Returns an iterator over references to the elements, from the oldest to the newest,
+    /// without consuming them.
+    pub fn iter(&self) -> Iter<'_, T> {
+        let slots = unsafe { self.map.as_ptr().add(DATA_OFFSET).cast() };
+        Iter::from_raw(slots, self.size, self.header().r as usize, self.len())
+    }
+
+    /// Drops all the elements, leaving the MmapCircularBuffer empty.
+    pub fn clear(&mut self) {
+        let header = self.header_mut();
+        header.r = 0;
+        header.len = 0;
+    }
+
+    /// Write the elements and the indexes to the file, returns once they are stored.
+    pub fn flush(&self) -> io::Result<()> {
+        self.map.flush()
+    }
+}
+
+/// Consume the elements, from the oldest to the newest.
+impl<T: Plain> Iterator for MmapCircularBuffer<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let r = self.header().r as usize;
+        let element = unsafe { self.slots().add(r).read() };
+        let size = self.size as u64;
+        let header = self.header_mut();
+        header.r = (header.r + 1) % size;
+        header.len -= 1;
+        Some(element)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
+
+impl<'a, T: Plain> IntoIterator for &'a MmapCircularBuffer<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T: Plain + core::fmt::Display> core::fmt::Display for MmapCircularBuffer<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fmt_elements(f, "MmapCircularBuffer", self.iter())
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,44 @@
+use crate::{Allocator, CircularBuffer, Global};
+
+/// A mutable reference to the oldest element of a CircularBuffer.
+///
+/// It is created by the `CircularBuffer::peek_mut` method. Dropping it leaves the element, with
+/// any change, in the CircularBuffer, while `PeekMut::pop` removes it.
+pub struct PeekMut<'a, T, P = crate::OldestFirst, A: Allocator = Global> {
+    buffer: &'a mut CircularBuffer<T, P, A>,
+}
+
+impl<'a, T, P, A: Allocator> PeekMut<'a, T, P, A> {
+    pub(crate) fn new(buffer: &'a mut CircularBuffer<T, P, A>) -> Option<Self> {
+        if buffer.is_empty() {
+            return None;
+        }
+        Some(PeekMut { buffer })
+    }
+
+    /// Remove the peeked element from the CircularBuffer and return it.
+    pub fn pop(this: PeekMut<'a, T, P, A>) -> T {
+        // the buffer is not empty, checked when the PeekMut was created
+        this.buffer.next().unwrap()
+    }
+}
+
+impl<'a, T, P, A: Allocator> core::ops::Deref for PeekMut<'a, T, P, A> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.buffer.ptr_at(0) }
+    }
+}
+
+impl<'a, T, P, A: Allocator> core::ops::DerefMut for PeekMut<'a, T, P, A> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.buffer.ptr_at(0) }
+    }
+}
+
+impl<'a, T: core::fmt::Debug, P, A: Allocator> core::fmt::Debug for PeekMut<'a, T, P, A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("PeekMut").field(&**self).finish()
+    }
+}
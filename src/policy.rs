@@ -0,0 +1,164 @@
+/// What to do when a new element is pushed into a full CircularBuffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Eviction {
+    /// Overwrite the oldest element, it is the default behaviour of the CircularBuffer.
+    Oldest,
+    /// Remove the element at the given position, where 0 is the oldest element, and push the new
+    /// element at the end of the CircularBuffer.
+    At(usize),
+    /// Leave the CircularBuffer untouched and drop the new element.
+    Reject,
+}
+
+/// Decides which element is evicted when a new element is pushed into a full CircularBuffer.
+///
+/// The policy is a type parameter of the CircularBuffer, the default `OldestFirst` policy is a
+/// zero sized type so it does not cost anything, neither in space nor in time.
+///
+/// The elements currently in the CircularBuffer are passed as two slices, `older` contains the
+/// oldest elements, `newer` the most recent ones. Both slices can be empty.
+pub trait EvictionPolicy<T> {
+    /// `true` if `evict` always returns `Eviction::Oldest`, it allows bulk operations to overwrite
+    /// many elements at once instead of asking the policy for every element. The elements
+    /// overwritten in bulk are dropped without calling `evicted`.
+    const OLDEST_FIRST: bool = false;
+
+    fn evict(&mut self, older: &[T], newer: &[T], value: &T) -> Eviction;
+
+    /// Receives the element that does not fit, after `evict`: the one evicted or, with
+    /// `Eviction::Reject`, the new one. By default it is dropped.
+    fn evicted(&mut self, element: T) {
+        drop(element);
+    }
+}
+
+/// Overwrite the oldest element.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OldestFirst;
+
+impl<T> EvictionPolicy<T> for OldestFirst {
+    const OLDEST_FIRST: bool = true;
+
+    #[inline(always)]
+    fn evict(&mut self, _older: &[T], _newer: &[T], _value: &T) -> Eviction {
+        Eviction::Oldest
+    }
+}
+
+/// Never overwrite, once the CircularBuffer is full new elements are dropped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Reject;
+
+impl<T> EvictionPolicy<T> for Reject {
+    #[inline(always)]
+    fn evict(&mut self, _older: &[T], _newer: &[T], _value: &T) -> Eviction {
+        Eviction::Reject
+    }
+}
+
+/// Keep the greatest elements, the smallest one is evicted.
+///
+/// If the new element is not greater than the smallest element in the CircularBuffer, the new
+/// element is rejected. Among equal elements, the oldest is evicted first.
+///
+/// The search for the smallest element runs in O(n) and the eviction moves the elements after it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Priority;
+
+impl<T: Ord> EvictionPolicy<T> for Priority {
+    fn evict(&mut self, older: &[T], newer: &[T], value: &T) -> Eviction {
+        let smallest = older
+            .iter()
+            .chain(newer.iter())
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.cmp(b));
+        match smallest {
+            Some((i, smallest)) if smallest < value => Eviction::At(i),
+            _ => Eviction::Reject,
+        }
+    }
+}
+
+/// Evict a random element.
+///
+/// The random numbers are generated with a xorshift generator from the given seed, so the same
+/// seed always evicts the same positions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        // xorshift gets stuck on zero
+        let state = if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed };
+        Random { state }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+}
+
+impl<T> EvictionPolicy<T> for Random {
+    fn evict(&mut self, older: &[T], newer: &[T], _value: &T) -> Eviction {
+        let len = older.len() + newer.len();
+        if len == 0 {
+            return Eviction::Reject;
+        }
+        Eviction::At((self.next_u64() % len as u64) as usize)
+    }
+}
+
+/// Hand the elements that do not fit to `handler`, instead of dropping them silently. The wrapped
+/// policy, `OldestFirst` by default, decides which ones.
+///
+/// The elements are always pushed one by one, so every element evicted reaches `handler`.
+///
+/// ```
+/// use rbl_circular_buffer::*;
+///
+/// let mut lost = Vec::new();
+/// let mut messages = CircularBuffer::with_policy(2, OnEvict::new(|id: u32| lost.push(id)));
+/// for id in 1..=4 {
+///     messages.push(id);
+/// }
+/// assert_eq!(messages, [3, 4]);
+/// drop(messages);
+/// assert_eq!(vec![1, 2], lost);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OnEvict<F, P = OldestFirst> {
+    policy: P,
+    handler: F,
+}
+
+impl<F> OnEvict<F> {
+    /// Overwrite the oldest element, like `OldestFirst`, and hand it to `handler`.
+    pub fn new(handler: F) -> Self {
+        OnEvict::with_policy(OldestFirst, handler)
+    }
+}
+
+impl<F, P> OnEvict<F, P> {
+    /// Wrap `policy`, the elements it evicts or rejects are handed to `handler`.
+    pub fn with_policy(policy: P, handler: F) -> Self {
+        OnEvict { policy, handler }
+    }
+}
+
+impl<T, F: FnMut(T), P: EvictionPolicy<T>> EvictionPolicy<T> for OnEvict<F, P> {
+    #[inline(always)]
+    fn evict(&mut self, older: &[T], newer: &[T], value: &T) -> Eviction {
+        self.policy.evict(older, newer, value)
+    }
+
+    fn evicted(&mut self, element: T) {
+        (self.handler)(element);
+    }
+}
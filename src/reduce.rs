@@ -0,0 +1,184 @@
+#[cfg(feature = "portable_simd")]
+use core::simd::{
+    cmp::SimdOrd,
+    num::{SimdFloat, SimdInt, SimdUint},
+    Simd,
+};
+
+use crate::{Allocator, CircularBuffer};
+
+/// Numeric types that can be summed and compared over a whole window.
+///
+/// It is implemented for the primitive integers and floats. Integers sums wrap on overflow,
+/// floats `min` and `max` ignore NaN values like `f64::min` and `f64::max`.
+///
+/// With the `portable_simd` feature, on nightly, the reductions use `std::simd`. Floats are then
+/// summed in a different order, and the result can differ in the last bits.
+pub trait Reduce: Copy + PartialOrd {
+    /// The sum of `values`, zero if `values` is empty.
+    fn sum_slice(values: &[Self]) -> Self;
+    /// The minimum of `values`, `None` if `values` is empty.
+    fn min_slice(values: &[Self]) -> Option<Self>;
+    /// The maximum of `values`, `None` if `values` is empty.
+    fn max_slice(values: &[Self]) -> Option<Self>;
+}
+
+impl<T: Reduce, P, A: Allocator> CircularBuffer<T, P, A> {
+    /// Returns the sum of the elements, zero if the CircularBuffer is empty.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(3);
+    /// buffer.extend_from_slice(&[1, 2, 3, 4]);
+    /// assert_eq!(9, buffer.window_sum());
+    /// assert_eq!(Some(2), buffer.window_min());
+    /// assert_eq!(Some(4), buffer.window_max());
+    /// ```
+    pub fn window_sum(&self) -> T {
+        let (older, newer) = self.as_raw_slices();
+        T::sum_slice(&[T::sum_slice(older), T::sum_slice(newer)])
+    }
+
+    /// Returns the smallest element, or `None` if the CircularBuffer is empty.
+    pub fn window_min(&self) -> Option<T> {
+        let (older, newer) = self.as_raw_slices();
+        match (T::min_slice(older), T::min_slice(newer)) {
+            (Some(a), Some(b)) => T::min_slice(&[a, b]),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Returns the greatest element, or `None` if the CircularBuffer is empty.
+    pub fn window_max(&self) -> Option<T> {
+        let (older, newer) = self.as_raw_slices();
+        match (T::max_slice(older), T::max_slice(newer)) {
+            (Some(a), Some(b)) => T::max_slice(&[a, b]),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+// the plain loops, used as they are without the `portable_simd` feature, and for the unaligned
+// ends of the slices with it
+trait Scalar: Sized {
+    fn scalar_sum(values: &[Self]) -> Self;
+    fn scalar_min(values: &[Self]) -> Option<Self>;
+    fn scalar_max(values: &[Self]) -> Option<Self>;
+}
+
+macro_rules! scalar_int {
+    ($($t:ty),*) => {
+        $(
+            impl Scalar for $t {
+                fn scalar_sum(values: &[$t]) -> $t {
+                    values.iter().fold(0, |acc, v| acc.wrapping_add(*v))
+                }
+
+                fn scalar_min(values: &[$t]) -> Option<$t> {
+                    values.iter().copied().min()
+                }
+
+                fn scalar_max(values: &[$t]) -> Option<$t> {
+                    values.iter().copied().max()
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! scalar_float {
+    ($($t:ty),*) => {
+        $(
+            impl Scalar for $t {
+                fn scalar_sum(values: &[$t]) -> $t {
+                    values.iter().sum()
+                }
+
+                fn scalar_min(values: &[$t]) -> Option<$t> {
+                    let (first, rest) = values.split_first()?;
+                    Some(rest.iter().fold(*first, |acc, v| acc.min(*v)))
+                }
+
+                fn scalar_max(values: &[$t]) -> Option<$t> {
+                    let (first, rest) = values.split_first()?;
+                    Some(rest.iter().fold(*first, |acc, v| acc.max(*v)))
+                }
+            }
+        )*
+    };
+}
+
+scalar_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+scalar_float!(f32, f64);
+
+#[cfg(not(feature = "portable_simd"))]
+macro_rules! impl_reduce {
+    ($($t:ty, $lanes:literal),*) => {
+        $(
+            impl Reduce for $t {
+                fn sum_slice(values: &[$t]) -> $t {
+                    <$t>::scalar_sum(values)
+                }
+
+                fn min_slice(values: &[$t]) -> Option<$t> {
+                    <$t>::scalar_min(values)
+                }
+
+                fn max_slice(values: &[$t]) -> Option<$t> {
+                    <$t>::scalar_max(values)
+                }
+            }
+        )*
+    };
+}
+
+// the unaligned head and tail are reduced one by one, the aligned middle `$lanes` at a time
+#[cfg(feature = "portable_simd")]
+macro_rules! impl_reduce {
+    ($($t:ty, $lanes:literal),*) => {
+        $(
+            impl Reduce for $t {
+                fn sum_slice(values: &[$t]) -> $t {
+                    let (head, chunks, tail) = values.as_simd::<$lanes>();
+                    let mut acc = Simd::<$t, $lanes>::splat(0 as $t);
+                    for chunk in chunks {
+                        acc += *chunk;
+                    }
+                    <$t>::scalar_sum(&[
+                        <$t>::scalar_sum(head),
+                        acc.reduce_sum(),
+                        <$t>::scalar_sum(tail),
+                    ])
+                }
+
+                fn min_slice(values: &[$t]) -> Option<$t> {
+                    let (head, chunks, tail) = values.as_simd::<$lanes>();
+                    let simd = chunks.split_first().map(|(first, rest)| {
+                        rest.iter().fold(*first, |acc, c| acc.simd_min(*c)).reduce_min()
+                    });
+                    [<$t>::scalar_min(head), simd, <$t>::scalar_min(tail)]
+                        .iter()
+                        .flatten()
+                        .fold(None, |acc, v| <$t>::scalar_min(&[acc.unwrap_or(*v), *v]))
+                }
+
+                fn max_slice(values: &[$t]) -> Option<$t> {
+                    let (head, chunks, tail) = values.as_simd::<$lanes>();
+                    let simd = chunks.split_first().map(|(first, rest)| {
+                        rest.iter().fold(*first, |acc, c| acc.simd_max(*c)).reduce_max()
+                    });
+                    [<$t>::scalar_max(head), simd, <$t>::scalar_max(tail)]
+                        .iter()
+                        .flatten()
+                        .fold(None, |acc, v| <$t>::scalar_max(&[acc.unwrap_or(*v), *v]))
+                }
+            }
+        )*
+    };
+}
+
+impl_reduce!(
+    i8, 32, i16, 16, i32, 8, i64, 4, isize, 4, u8, 32, u16, 16, u32, 8, u64, 4, usize, 4, f32, 8,
+    f64, 4
+);
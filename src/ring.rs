@@ -0,0 +1,101 @@
+use core::mem::MaybeUninit;
+
+use crate::Iter;
+
+// The indexes of a circular buffer over slots it does not allocate, shared by
+// `StaticCircularBuffer` and `BorrowedCircularBuffer`.
+//
+// Only the slots between the reading and the writing pointer are initialized, so every method
+// must always be called with the same slots.
+pub(crate) struct Ring {
+    // writing pointer
+    w: usize,
+    // reading pointer
+    r: usize,
+    full: bool,
+}
+
+impl Ring {
+    pub(crate) const fn new() -> Self {
+        Ring {
+            w: 0,
+            r: 0,
+            full: false,
+        }
+    }
+
+    pub(crate) fn len(&self, size: usize) -> usize {
+        if self.full {
+            size
+        } else if self.w >= self.r {
+            self.w - self.r
+        } else {
+            size - self.r + self.w
+        }
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        self.full
+    }
+
+    // returns the empty slots left, like `CircularBuffer::push`
+    pub(crate) fn push<T>(&mut self, slots: &mut [MaybeUninit<T>], value: T) -> usize {
+        let size = slots.len();
+        // a zero sized buffer cannot hold anything, the value is dropped
+        if size == 0 {
+            return 0;
+        }
+        // the oldest element is read out of its slot before the new one is written, and dropped
+        // last, so the ring is consistent even if its destructor panics
+        let oldest = if self.full {
+            let oldest = unsafe { slots[self.w].assume_init_read() };
+            self.r = (self.r + 1) % size;
+            self.full = false;
+            Some(oldest)
+        } else {
+            None
+        };
+        slots[self.w].write(value);
+        self.w = (self.w + 1) % size;
+        self.full = self.w == self.r;
+        drop(oldest);
+        size - self.len(size)
+    }
+
+    pub(crate) fn pop<T>(&mut self, slots: &mut [MaybeUninit<T>]) -> Option<T> {
+        if self.len(slots.len()) == 0 {
+            return None;
+        }
+        let element = unsafe { slots[self.r].assume_init_read() };
+        self.r = (self.r + 1) % slots.len();
+        self.full = false;
+        Some(element)
+    }
+
+    pub(crate) fn iter<'a, T>(&self, slots: &'a [MaybeUninit<T>]) -> Iter<'a, T> {
+        Iter::from_raw(slots.as_ptr().cast(), slots.len(), self.r, self.len(slots.len()))
+    }
+
+    pub(crate) fn clear<T>(&mut self, slots: &mut [MaybeUninit<T>]) {
+        while self.pop(slots).is_some() {}
+    }
+}
+
+// writes `name(a, b, c)`, or `name(<empty>)`, like the Display of CircularBuffer
+pub(crate) fn fmt_elements<T: core::fmt::Display>(
+    f: &mut core::fmt::Formatter<'_>,
+    name: &str,
+    iter: Iter<'_, T>,
+) -> core::fmt::Result {
+    if iter.len() == 0 {
+        return write!(f, "{}(<empty>)", name);
+    }
+    write!(f, "{}(", name)?;
+    for (i, element) in iter.enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        core::fmt::Display::fmt(element, f)?;
+    }
+    write!(f, ")")
+}
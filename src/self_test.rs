@@ -0,0 +1,115 @@
+use core::mem::MaybeUninit;
+
+use crate::{BorrowedCircularBuffer, SelfTestError};
+
+// written over the whole storage, to catch bits stuck at zero or one and shorted neighbours
+const PATTERNS: [u32; 4] = [0x0000_0000, 0xFFFF_FFFF, 0xAAAA_AAAA, 0x5555_5555];
+
+impl<'a> BorrowedCircularBuffer<'a, u32> {
+    /// Run a fixed sequence of pushes, overwrites and pops over `storage`, checking every value
+    /// read and the length of the buffer after every operation.
+    ///
+    /// It is meant to validate both the RAM and the ring at boot: every slot is written with
+    /// values derived from four bit patterns, and the reading and writing pointers wrap around
+    /// the storage. The sequence is always the same, so a failure can be reproduced.
+    ///
+    /// Returns the number of checks performed, or the first one that failed. The storage is
+    /// left uninitialized.
+    ///
+    /// ```
+    /// use core::mem::MaybeUninit;
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut storage = [MaybeUninit::uninit(); 64];
+    /// assert!(BorrowedCircularBuffer::self_test(&mut storage).is_ok());
+    /// ```
+    pub fn self_test(storage: &'a mut [MaybeUninit<u32>]) -> Result<usize, SelfTestError> {
+        let capacity = storage.len();
+        let mut test = SelfTest {
+            buffer: BorrowedCircularBuffer::from_storage(storage),
+            checks: 0,
+            mask: 0,
+            oldest: 0,
+            next: 0,
+        };
+        for mask in PATTERNS {
+            test.mask = mask;
+            // overwrite the oldest half, then wrap the reading pointer and the writing one again
+            test.push(capacity + capacity / 2 + 1)?;
+            test.check_window()?;
+            test.pop(capacity / 2 + 1)?;
+            test.push(capacity / 3 + 1)?;
+            test.check_window()?;
+            test.pop(capacity)?;
+            test.check_len()?;
+        }
+        Ok(test.checks)
+    }
+}
+
+struct SelfTest<'a> {
+    buffer: BorrowedCircularBuffer<'a, u32>,
+    checks: usize,
+    mask: u32,
+    // sequence numbers of the oldest element in the buffer and of the next one pushed
+    oldest: usize,
+    next: usize,
+}
+
+impl<'a> SelfTest<'a> {
+    fn value(&self, sequence: usize) -> u32 {
+        self.mask ^ (sequence as u32).wrapping_mul(0x9E37_79B9)
+    }
+
+    fn check(&mut self, expected: u32, found: Option<u32>) -> Result<(), SelfTestError> {
+        if found != Some(expected) {
+            return Err(SelfTestError::new(self.checks, expected, found));
+        }
+        self.checks += 1;
+        Ok(())
+    }
+
+    fn check_len(&mut self) -> Result<(), SelfTestError> {
+        let len = self.buffer.len();
+        self.check((self.next - self.oldest) as u32, Some(len as u32))
+    }
+
+    fn check_window(&mut self) -> Result<(), SelfTestError> {
+        self.check_len()?;
+        let mut iter = self.buffer.iter();
+        for sequence in self.oldest..self.next {
+            let found = iter.next().copied();
+            if found != Some(self.value(sequence)) {
+                return Err(SelfTestError::new(self.checks, self.value(sequence), found));
+            }
+            self.checks += 1;
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, n: usize) -> Result<(), SelfTestError> {
+        for _ in 0..n {
+            let value = self.value(self.next);
+            self.buffer.push(value);
+            self.next += 1;
+            if self.next - self.oldest > self.buffer.capacity() {
+                self.oldest += 1;
+            }
+            self.check_len()?;
+        }
+        Ok(())
+    }
+
+    fn pop(&mut self, n: usize) -> Result<(), SelfTestError> {
+        for _ in 0..n {
+            if self.oldest == self.next {
+                break;
+            }
+            let found = self.buffer.next();
+            self.check(self.value(self.oldest), found)?;
+            self.oldest += 1;
+            self.check_len()?;
+        }
+        Ok(())
+    }
+}
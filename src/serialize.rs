@@ -0,0 +1,91 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+
+use crate::{Allocator, CircularBuffer, Iter};
+
+/// Serialize the CircularBuffer as a tuple of its capacity and its elements, from the oldest.
+///
+/// The policy and the growth limit are not serialized.
+///
+/// ```
+/// use rbl_circular_buffer::*;
+///
+/// let mut buffer = CircularBuffer::new(3);
+/// buffer.extend_from_slice(&[1, 2, 3, 4]);
+/// let json = serde_json::to_string(&buffer).unwrap();
+/// assert_eq!("[3,[2,3,4]]", json);
+/// let back: CircularBuffer<u32> = serde_json::from_str(&json).unwrap();
+/// assert_eq!("CircularBuffer(2, 3, 4)", format!("{}", back));
+/// ```
+impl<T: Serialize, P, A: Allocator> Serialize for CircularBuffer<T, P, A> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&self.capacity())?;
+        tuple.serialize_element(&Elements(self.iter()))?;
+        tuple.end()
+    }
+}
+
+struct Elements<'a, T>(Iter<'a, T>);
+
+impl<'a, T: Serialize> Serialize for Elements<'a, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.clone())
+    }
+}
+
+/// Deserialize a CircularBuffer serialized as a tuple of its capacity and its elements.
+///
+/// It fails if there are more elements than the capacity.
+///
+/// The capacity comes from the input, so it is not trusted: when the array for it would take more
+/// than 1 MiB and more than the elements, the CircularBuffer starts with a smaller array, that
+/// grows as elements are pushed, and still reports and serializes the capacity it was given.
+///
+/// ```
+/// use rbl_circular_buffer::*;
+///
+/// let mut buffer: CircularBuffer<u64> = serde_json::from_str("[1000000000000,[1,2]]").unwrap();
+/// assert_eq!(1_000_000_000_000, buffer.capacity());
+/// assert!(buffer.memory_usage() < 2 * 1024 * 1024);
+/// buffer.push(3);
+/// assert_eq!("[1000000000000,[1,2,3]]", serde_json::to_string(&buffer).unwrap());
+/// ```
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for CircularBuffer<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_tuple(2, CircularBufferVisitor(PhantomData))
+    }
+}
+
+struct CircularBufferVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for CircularBufferVisitor<T> {
+    type Value = CircularBuffer<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a tuple of a capacity and the elements")
+    }
+
+    fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<Self::Value, S::Error> {
+        let capacity: usize = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let elements: Vec<T> = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        if elements.len() > capacity {
+            return Err(de::Error::invalid_length(
+                elements.len(),
+                &"at most as many elements as the capacity",
+            ));
+        }
+        let mut buffer = CircularBuffer::try_for_deserialize(capacity, elements.len())
+            .map_err(de::Error::custom)?;
+        buffer.extend(elements);
+        Ok(buffer)
+    }
+}
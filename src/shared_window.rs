@@ -0,0 +1,93 @@
+use alloc::sync::Arc;
+
+use crate::CircularBuffer;
+
+/// A CircularBuffer whose clones share the same storage.
+///
+/// Cloning a SharedWindow is O(1), it only increments a reference count. The elements are copied
+/// only when a shared window gets mutated, at that point the window that is being mutated makes its
+/// own private copy of the CircularBuffer and the other clones are left untouched.
+///
+/// Reading a SharedWindow never copies, it derefs to the underlying `CircularBuffer`.
+///
+/// ```
+/// use rbl_circular_buffer::*;
+///
+/// let mut window = SharedWindow::new(3);
+/// window.push(1);
+/// window.push(2);
+///
+/// let snapshot = window.clone();
+/// assert!(window.is_shared());
+///
+/// // the first push after the clone copies the elements
+/// window.push(3);
+/// assert!(!window.is_shared());
+///
+/// assert_eq!(3, window.len());
+/// assert_eq!(2, snapshot.len());
+/// ```
+pub struct SharedWindow<T> {
+    inner: Arc<CircularBuffer<T>>,
+}
+
+impl<T> SharedWindow<T> {
+    /// Create a new SharedWindow of size `size`.
+    pub fn new(size: usize) -> Self {
+        SharedWindow {
+            inner: Arc::new(CircularBuffer::new(size)),
+        }
+    }
+
+    /// Returns `true` if the storage is shared with at least another SharedWindow.
+    pub fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.inner) > 1
+    }
+}
+
+impl<T: Clone> SharedWindow<T> {
+    /// Returns a mutable reference to the underlying CircularBuffer.
+    ///
+    /// If the storage is shared, the elements are copied first, so the other windows will not see
+    /// the mutation.
+    pub fn make_mut(&mut self) -> &mut CircularBuffer<T> {
+        Arc::make_mut(&mut self.inner)
+    }
+
+    /// Push a new element into the window, copying the elements first if the storage is shared.
+    ///
+    /// It follows the same semantics of `CircularBuffer::push`.
+    pub fn push(&mut self, value: T) -> usize {
+        self.make_mut().push(value)
+    }
+}
+
+impl<T> Clone for SharedWindow<T> {
+    fn clone(&self) -> Self {
+        SharedWindow {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T> From<CircularBuffer<T>> for SharedWindow<T> {
+    fn from(buffer: CircularBuffer<T>) -> Self {
+        SharedWindow {
+            inner: Arc::new(buffer),
+        }
+    }
+}
+
+impl<T> core::ops::Deref for SharedWindow<T> {
+    type Target = CircularBuffer<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for SharedWindow<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(&*self.inner, f)
+    }
+}
@@ -0,0 +1,49 @@
+use crate::iter::Iter;
+use crate::{Allocator, CircularBuffer};
+
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+impl<T: Copy + Into<f64>, P, A: Allocator> CircularBuffer<T, P, A> {
+    /// Write the elements, from the oldest, as a sparkline of unicode block characters.
+    ///
+    /// The bars are scaled between the minimum and the maximum element, a window where all the
+    /// elements are equal is drawn with the lowest bar. NaN values are drawn as spaces.
+    ///
+    /// Nothing is allocated, the characters are written directly into `f`.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(8);
+    /// for i in [0u8, 1, 2, 3, 4, 5, 6, 7] {
+    ///     buffer.push(i);
+    /// }
+    /// let mut line = String::new();
+    /// buffer.sparkline(&mut line).unwrap();
+    /// assert_eq!("▁▂▃▄▅▆▇█", line);
+    /// ```
+    pub fn sparkline<W: core::fmt::Write>(&self, f: &mut W) -> core::fmt::Result {
+        let mut min = f64::INFINITY;
+        let mut max = f64::NEG_INFINITY;
+        for value in Iter::new(self) {
+            let value: f64 = (*value).into();
+            min = min.min(value);
+            max = max.max(value);
+        }
+        let range = max - min;
+        for value in Iter::new(self) {
+            let value: f64 = (*value).into();
+            if value.is_nan() {
+                f.write_char(' ')?;
+                continue;
+            }
+            let level = if range > 0.0 {
+                ((value - min) / range * (BARS.len() - 1) as f64).round() as usize
+            } else {
+                0
+            };
+            f.write_char(BARS[level])?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{Allocator, CircularBuffer, Drain, Global};
+
+/// A splicing iterator over a range of elements of a CircularBuffer.
+///
+/// It is created by the `CircularBuffer::splice` method. It yields the elements removed from the
+/// range, when it is dropped the elements not consumed yet are dropped and the replacement
+/// elements are written in their place.
+pub struct Splice<'a, I: Iterator, P = crate::OldestFirst, A: Allocator = Global> {
+    drain: Drain<'a, I::Item, P, A>,
+    replace_with: I,
+}
+
+impl<'a, I: Iterator, P, A: Allocator> Splice<'a, I, P, A> {
+    pub(crate) fn new(drain: Drain<'a, I::Item, P, A>, replace_with: I) -> Self {
+        Splice {
+            drain,
+            replace_with,
+        }
+    }
+}
+
+impl<T, P, A: Allocator> CircularBuffer<T, P, A> {
+    /// Replace the elements in `range` with the elements of `replace_with`, and return the
+    /// elements removed in an iterator.
+    ///
+    /// The range is over the logical positions of the elements, 0 is the oldest element. Like
+    /// `drain`, the elements are removed even if the iterator is not fully consumed, the
+    /// replacement elements are written when the iterator is dropped.
+    ///
+    /// The CircularBuffer never grows nor evicts: the replacement elements fill the range, then
+    /// the free space, and the ones that do not fit are not taken from `replace_with`.
+    ///
+    /// Panics if the start of the range is greater than its end or if the end is greater than the
+    /// length of the CircularBuffer.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut history = CircularBuffer::new(5);
+    /// history.extend_from_slice(&[1, 2, -1, -1, 5]);
+    /// let corrupted: Vec<_> = history.splice(2..4, [3, 4]).collect();
+    /// assert_eq!(vec![-1, -1], corrupted);
+    /// assert_eq!(history, [1, 2, 3, 4, 5]);
+    ///
+    /// history.splice(..1, [7, 8, 9]).for_each(drop);
+    /// assert_eq!(history, [7, 2, 3, 4, 5]);
+    /// ```
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<'_, I::IntoIter, P, A>
+    where
+        R: core::ops::RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        Splice::new(self.drain(range), replace_with.into_iter())
+    }
+}
+
+impl<'a, I: Iterator, P, A: Allocator> Iterator for Splice<'a, I, P, A> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        self.drain.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.drain.size_hint()
+    }
+}
+
+impl<'a, I: Iterator, P, A: Allocator> DoubleEndedIterator for Splice<'a, I, P, A> {
+    fn next_back(&mut self) -> Option<I::Item> {
+        self.drain.next_back()
+    }
+}
+
+impl<'a, I: Iterator, P, A: Allocator> ExactSizeIterator for Splice<'a, I, P, A> {}
+
+impl<'a, I: Iterator, P, A: Allocator> Drop for Splice<'a, I, P, A> {
+    fn drop(&mut self) {
+        self.drain.by_ref().for_each(drop);
+        // the drain closes the gap left when it is dropped
+        self.drain.fill_gap(&mut self.replace_with);
+    }
+}
@@ -0,0 +1,109 @@
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+use crate::{Allocator, CircularBuffer, Global};
+
+/// The pushing half of a CircularBuffer borrowed with `CircularBuffer::split_mut`.
+///
+/// A full buffer rejects the new elements, the oldest one may be borrowed by the `Reader`.
+pub struct Writer<'a, T, P = crate::OldestFirst, A: Allocator = Global> {
+    buffer: NonNull<CircularBuffer<T, P, A>>,
+    _marker: PhantomData<&'a mut CircularBuffer<T, P, A>>,
+}
+
+/// The popping half of a CircularBuffer borrowed with `CircularBuffer::split_mut`.
+pub struct Reader<'a, T, P = crate::OldestFirst, A: Allocator = Global> {
+    buffer: NonNull<CircularBuffer<T, P, A>>,
+    _marker: PhantomData<&'a mut CircularBuffer<T, P, A>>,
+}
+
+// The two halves point to the same CircularBuffer, they are not `Send`, so they are used from a
+// single thread and never at the same time. The Writer only writes the free slots and the Reader
+// only reads the slots holding elements, so a reference returned by `Reader::peek` is never
+// overwritten: the Writer never evicts nor reallocates.
+
+impl<T, P, A: Allocator> CircularBuffer<T, P, A> {
+    /// Borrow the CircularBuffer as a `Writer` that can only push and a `Reader` that can only
+    /// pop and peek, so the two halves can be kept in different places.
+    ///
+    /// Nothing is copied or allocated, and nothing is atomic: the halves stay in the thread of
+    /// the CircularBuffer. Use `split` to push and pop from two threads.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = CircularBuffer::new(2);
+    /// {
+    ///     let (mut writer, mut reader) = buffer.split_mut();
+    ///     writer.push(1).unwrap();
+    ///     let oldest = reader.peek();
+    ///     writer.push(2).unwrap();
+    ///     assert_eq!(Err(3), writer.push(3));
+    ///     assert_eq!(Some(&1), oldest);
+    ///     assert_eq!(Some(1), reader.pop());
+    /// }
+    /// assert_eq!("CircularBuffer(2)", format!("{}", buffer));
+    /// ```
+    pub fn split_mut(&mut self) -> (Writer<'_, T, P, A>, Reader<'_, T, P, A>) {
+        let buffer = NonNull::from(self);
+        (
+            Writer {
+                buffer,
+                _marker: PhantomData,
+            },
+            Reader {
+                buffer,
+                _marker: PhantomData,
+            },
+        )
+    }
+}
+
+impl<'a, T, P, A: Allocator> Writer<'a, T, P, A> {
+    /// Push a new element in O(1), returns the empty spots left.
+    ///
+    /// If the CircularBuffer is full the element is given back in the error, whatever its
+    /// policy, and growable CircularBuffers do not grow.
+    pub fn push(&mut self, value: T) -> Result<usize, T> {
+        let buffer = unsafe { self.buffer.as_mut() };
+        if buffer.len() == buffer.size {
+            return Err(value);
+        }
+        buffer.write_slot(value);
+        buffer.full = buffer.w == buffer.r;
+        Ok(buffer.free_space())
+    }
+
+    /// Returns the number of elements that can be pushed before the CircularBuffer is full.
+    pub fn free_space(&self) -> usize {
+        unsafe { self.buffer.as_ref() }.free_space()
+    }
+}
+
+impl<'a, T, P, A: Allocator> Reader<'a, T, P, A> {
+    /// Remove the oldest element and return it, or `None` if the CircularBuffer is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        unsafe { self.buffer.as_mut() }.next()
+    }
+
+    /// Returns a reference to the oldest element, or `None` if the CircularBuffer is empty.
+    ///
+    /// The Writer can keep pushing while the reference is alive.
+    pub fn peek(&self) -> Option<&T> {
+        let buffer = unsafe { self.buffer.as_ref() };
+        if buffer.is_empty() {
+            return None;
+        }
+        unsafe { Some(&*buffer.ptr_at(0)) }
+    }
+
+    /// Returns the amount of elements in the CircularBuffer.
+    pub fn len(&self) -> usize {
+        unsafe { self.buffer.as_ref() }.len()
+    }
+
+    /// Returns `true` if there are no elements in the CircularBuffer.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
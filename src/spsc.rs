@@ -0,0 +1,196 @@
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{Allocator, CircularBuffer};
+
+// The slots shared by a Producer and a Consumer.
+//
+// `head` and `tail` count the elements popped and pushed since the split, wrapping on overflow,
+// the element `i` is in the slot `i % capacity`. Only the Consumer stores `head` and only the
+// Producer stores `tail`, so each side owns the slots the other one cannot touch: the Producer
+// the free ones, the Consumer the ones between `head` and `tail`.
+struct Shared<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    head: AtomicUsize,
+    tail: AtomicUsize,
+}
+
+// the elements are moved between the two threads, never shared
+unsafe impl<T: Send> Send for Shared<T> {}
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+impl<T> Shared<T> {
+    fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn len(&self) -> usize {
+        // `head` first, so it is never ahead of `tail`, the two loads may be apart by a few
+        // pushes, never more than the capacity
+        let head = self.head.load(Ordering::Acquire);
+        let tail = self.tail.load(Ordering::Acquire);
+        core::cmp::min(tail.wrapping_sub(head), self.capacity())
+    }
+
+    fn slot(&self, i: usize) -> *mut MaybeUninit<T> {
+        self.slots[i % self.capacity()].get()
+    }
+}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        let head = *self.head.get_mut();
+        let tail = *self.tail.get_mut();
+        let mut i = head;
+        while i != tail {
+            unsafe { (*self.slot(i)).assume_init_drop() };
+            i = i.wrapping_add(1);
+        }
+    }
+}
+
+/// The pushing half of a CircularBuffer split between two threads.
+///
+/// It is created by `CircularBuffer::split`. It can be moved to another thread, and pushes
+/// without locks while the `Consumer` pops.
+pub struct Producer<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// The popping half of a CircularBuffer split between two threads.
+///
+/// It is created by `CircularBuffer::split`. It can be moved to another thread, and pops without
+/// locks while the `Producer` pushes.
+pub struct Consumer<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T, P, A: Allocator> CircularBuffer<T, P, A> {
+    /// Split the CircularBuffer into a `Producer` and a `Consumer` that can be used from two
+    /// different threads without a lock.
+    ///
+    /// The elements are moved, from the oldest, into a new array of the same capacity, the
+    /// indexes are atomics. Since the Producer cannot drop an element the Consumer may be
+    /// reading, a full buffer rejects the new elements instead of overwriting the oldest one.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let (mut producer, mut consumer) = CircularBuffer::new(16).split();
+    /// let handle = std::thread::spawn(move || {
+    ///     for i in 0..100 {
+    ///         while producer.push(i).is_err() {
+    ///             std::thread::yield_now();
+    ///         }
+    ///     }
+    /// });
+    /// let mut sum = 0;
+    /// for _ in 0..100 {
+    ///     loop {
+    ///         if let Some(i) = consumer.pop() {
+    ///             sum += i;
+    ///             break;
+    ///         }
+    ///     }
+    /// }
+    /// handle.join().unwrap();
+    /// assert_eq!(4950, sum);
+    /// ```
+    pub fn split(mut self) -> (Producer<T>, Consumer<T>) {
+        let capacity = self.capacity();
+        let mut slots = Vec::with_capacity(capacity);
+        slots.extend(self.by_ref().map(|e| UnsafeCell::new(MaybeUninit::new(e))));
+        let len = slots.len();
+        slots.resize_with(capacity, || UnsafeCell::new(MaybeUninit::uninit()));
+        let shared = Arc::new(Shared {
+            slots: slots.into_boxed_slice(),
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(len),
+        });
+        (
+            Producer {
+                shared: shared.clone(),
+            },
+            Consumer { shared },
+        )
+    }
+}
+
+impl<T> Producer<T> {
+    /// Push a new element in O(1), returns the empty spots left.
+    ///
+    /// If the buffer is full the element is given back in the error.
+    pub fn push(&mut self, value: T) -> Result<usize, T> {
+        let shared = &*self.shared;
+        let tail = shared.tail.load(Ordering::Relaxed);
+        // the Consumer is done with the slot once `head` moves past it
+        let head = shared.head.load(Ordering::Acquire);
+        let len = tail.wrapping_sub(head);
+        if len == shared.capacity() {
+            return Err(value);
+        }
+        unsafe { (*shared.slot(tail)).write(value) };
+        shared.tail.store(tail.wrapping_add(1), Ordering::Release);
+        Ok(shared.capacity() - len - 1)
+    }
+
+    /// Returns the number of elements not popped yet, it may change as soon as it is returned.
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    /// Returns `true` if all the elements have been popped.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements the buffer can hold.
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity()
+    }
+}
+
+impl<T> Consumer<T> {
+    /// Remove the oldest element and return it, or `None` if the buffer is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        let shared = &*self.shared;
+        let head = shared.head.load(Ordering::Relaxed);
+        // the element is written before `tail` moves past it
+        let tail = shared.tail.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+        let element = unsafe { (*shared.slot(head)).assume_init_read() };
+        shared.head.store(head.wrapping_add(1), Ordering::Release);
+        Some(element)
+    }
+
+    /// Returns the number of elements ready to be popped, more may be pushed meanwhile.
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    /// Returns `true` if there is no element to pop.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements the buffer can hold.
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity()
+    }
+}
+
+/// Pop the elements, it returns `None` as soon as the buffer is empty, even if the Producer will
+/// push again.
+impl<T> Iterator for Consumer<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.pop()
+    }
+}
@@ -0,0 +1,181 @@
+use core::mem::MaybeUninit;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::ring::{fmt_elements, Ring};
+use crate::{Iter, IterRev};
+
+/// A circular buffer of `N` elements stored inline, it never allocates.
+///
+/// It has the same semantics of a `CircularBuffer` with the default `OldestFirst` policy: when
+/// it is full, `push` overwrites the oldest element. Since `new` is a `const fn`, it can be
+/// created in a `const` or a `static`, where the heap is not available.
+///
+/// ```
+/// use rbl_circular_buffer::*;
+///
+/// let mut buffer: StaticCircularBuffer<u32, 3> = StaticCircularBuffer::new();
+/// for i in 0..5 {
+///     buffer.push(i);
+/// }
+/// assert_eq!(3, buffer.len());
+///
+/// let mut v = Vec::with_capacity(2);
+/// buffer.fill(&mut v);
+/// assert_eq!(vec![2, 3], v);
+/// assert_eq!("StaticCircularBuffer(4)", format!("{}", buffer));
+/// ```
+pub struct StaticCircularBuffer<T, const N: usize> {
+    // only the slots between the reading and the writing pointer are initialized
+    buffer: [MaybeUninit<T>; N],
+    ring: Ring,
+}
+
+impl<T, const N: usize> StaticCircularBuffer<T, N> {
+    /// Create a new, empty, StaticCircularBuffer of size `N`.
+    ///
+    /// A StaticCircularBuffer of size 0 is valid, it holds nothing and drops every element
+    /// pushed.
+    pub const fn new() -> Self {
+        StaticCircularBuffer {
+            buffer: [const { MaybeUninit::uninit() }; N],
+            ring: Ring::new(),
+        }
+    }
+
+    /// Returns the amount of elements in the StaticCircularBuffer in O(1)
+    pub fn len(&self) -> usize {
+        self.ring.len(N)
+    }
+
+    /// Returns `true` if there are no elements in the StaticCircularBuffer.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements the StaticCircularBuffer can hold, `N`.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns `true` if the StaticCircularBuffer is full, the next `push` will overwrite the
+    /// oldest element.
+    pub fn is_full(&self) -> bool {
+        self.ring.is_full()
+    }
+
+    /// Returns the number of elements that can be pushed before the StaticCircularBuffer is full.
+    pub fn free_space(&self) -> usize {
+        N - self.len()
+    }
+
+    /// Push a new element into the StaticCircularBuffer in O(1), returns the empty spots left.
+    ///
+    /// If the StaticCircularBuffer is full, the oldest element is overwritten.
+    pub fn push(&mut self, value: T) -> usize {
+        self.ring.push(&mut self.buffer, value)
+    }
+
+    /// Moves the oldest elements into `return_vector`, without allocating, it returns the number
+    /// of elements moved.
+    ///
+    /// It behaves like `CircularBuffer::fill`: only the spare capacity of the vector is filled.
+    #[cfg(feature = "alloc")]
+    pub fn fill(&mut self, return_vector: &mut Vec<T>) -> usize {
+        let mut i = 0;
+        while return_vector.capacity() - return_vector.len() > 0 {
+            match self.next() {
+                Some(element) => {
+                    return_vector.push(element);
+                    i += 1;
+                }
+                None => return i,
+            }
+        }
+        i
+    }
+
+    /// Moves the oldest elements into `out`, from its start, it returns the number of elements
+    /// moved.
+    ///
+    /// It behaves like `CircularBuffer::fill_slice`, and needs no allocator.
+    ///
+    /// ```
+    /// use rbl_circular_buffer::*;
+    ///
+    /// let mut buffer = StaticCircularBuffer::<u8, 4>::new();
+    /// for i in 1..=3 {
+    ///     buffer.push(i);
+    /// }
+    /// let mut out = [0; 8];
+    /// assert_eq!(3, buffer.fill_slice(&mut out));
+    /// assert_eq!([1, 2, 3], out[..3]);
+    /// assert!(buffer.is_empty());
+    /// ```
+    pub fn fill_slice(&mut self, out: &mut [T]) -> usize {
+        let mut i = 0;
+        for (slot, element) in out.iter_mut().zip(self.by_ref()) {
+            *slot = element;
+            i += 1;
+        }
+        i
+    }
+
+    /// Returns an iterator over references to the elements, from the oldest to the newest,
+    /// without consuming them.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.ring.iter(&self.buffer)
+    }
+
+    /// Returns an iterator over references to the elements, from the newest to the oldest,
+    /// without consuming them.
+    pub fn iter_rev(&self) -> IterRev<'_, T> {
+        IterRev::new(self.iter())
+    }
+
+    /// Drops all the elements, leaving the StaticCircularBuffer empty.
+    pub fn clear(&mut self) {
+        self.ring.clear(&mut self.buffer);
+    }
+}
+
+impl<T, const N: usize> Default for StaticCircularBuffer<T, N> {
+    fn default() -> Self {
+        StaticCircularBuffer::new()
+    }
+}
+
+impl<T, const N: usize> Drop for StaticCircularBuffer<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// Consume the elements, from the oldest to the newest.
+impl<T, const N: usize> Iterator for StaticCircularBuffer<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.ring.pop(&mut self.buffer)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a StaticCircularBuffer<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T: core::fmt::Display, const N: usize> core::fmt::Display for StaticCircularBuffer<T, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fmt_elements(f, "StaticCircularBuffer", self.iter())
+    }
+}
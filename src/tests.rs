@@ -128,7 +128,7 @@ fn size_2_adding_3_remove_2_add_1_remove_0() {
 fn fast_fill_test1() {
     let mut a = CircularBuffer::new(4);
     let mut b = CircularBuffer::new(4);
-    for i in vec![1, 2, 3, 4] {
+    for i in [1, 2, 3, 4] {
         a.push(i);
         b.push(i);
     }
@@ -204,7 +204,7 @@ proptest! {
             let mut drainer = Vec::with_capacity(to_remove);
             let removed = b.fill(&mut drainer);
             assert_eq!(std::cmp::min(counted_len, to_remove), removed);
-            counted_len = counted_len - removed;
+            counted_len -= removed;
             assert_eq!(counted_len, b.len());
             assert!(r.contains(&counted_len));
         }
@@ -290,14 +290,14 @@ fn test_display() {
 }
 
 #[test]
-fn copy_works_as_expected() {
+fn clone_works_as_expected() {
     let mut b = CircularBuffer::new(5);
 
     for i in 0..10 {
         b.push(i);
     }
 
-    let mut b_copy = b;
+    let mut b_copy = b.clone();
     let mut v1 = Vec::with_capacity(5);
     let mut v2 = Vec::with_capacity(5);
 
@@ -335,3 +335,2584 @@ fn test_clone() {
     let c1: Vec<_> = c.collect();
     assert_eq!(c1[0].a, "1");
 }
+
+#[test]
+fn clone_is_a_deep_copy() {
+    let counter = std::rc::Rc::new(());
+    let mut b = CircularBuffer::new(3);
+    for _ in 0..5 {
+        b.push(counter.clone());
+    }
+    let mut c = b.clone();
+    assert_eq!(7, std::rc::Rc::strong_count(&counter));
+
+    b.clear();
+    assert_eq!(3, c.len());
+    assert_eq!(4, std::rc::Rc::strong_count(&counter));
+
+    c.pop_front();
+    drop(c);
+    assert_eq!(1, std::rc::Rc::strong_count(&counter));
+}
+
+struct PanicOnClone(std::rc::Rc<()>);
+
+impl Clone for PanicOnClone {
+    fn clone(&self) -> Self {
+        if std::rc::Rc::strong_count(&self.0) > 4 {
+            panic!("clone failed");
+        }
+        PanicOnClone(self.0.clone())
+    }
+}
+
+#[test]
+fn clone_panicking_does_not_drop_twice() {
+    let counter = std::rc::Rc::new(());
+    let mut b = CircularBuffer::new(3);
+    b.push(PanicOnClone(counter.clone()));
+    b.push(PanicOnClone(counter.clone()));
+    b.push(PanicOnClone(counter.clone()));
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| b.clone()));
+    assert!(result.is_err());
+    assert_eq!(3, b.len());
+    drop(b);
+    // the element cloned before the panic is dropped as well
+    assert_eq!(1, std::rc::Rc::strong_count(&counter));
+}
+
+struct PanicOnFormat(std::rc::Rc<()>);
+
+impl core::fmt::Debug for PanicOnFormat {
+    fn fmt(&self, _: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        panic!("debug failed, {} references", std::rc::Rc::strong_count(&self.0));
+    }
+}
+
+impl core::fmt::Display for PanicOnFormat {
+    fn fmt(&self, _: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Err(core::fmt::Error)
+    }
+}
+
+#[test]
+fn formatting_failing_does_not_drop_the_elements() {
+    use std::fmt::Write;
+    let counter = std::rc::Rc::new(());
+    let mut b = CircularBuffer::new(2);
+    for _ in 0..3 {
+        b.push(PanicOnFormat(counter.clone()));
+    }
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| format!("{:?}", b)));
+    assert!(result.is_err());
+    assert!(write!(String::new(), "{}", b).is_err());
+    assert_eq!(3, std::rc::Rc::strong_count(&counter));
+    drop(b);
+    assert_eq!(1, std::rc::Rc::strong_count(&counter));
+}
+
+#[test]
+fn clear_drops_all_the_elements() {
+    let counter = std::rc::Rc::new(());
+    let mut b = CircularBuffer::new(3);
+    for _ in 0..5 {
+        b.push(counter.clone());
+    }
+    assert_eq!(4, std::rc::Rc::strong_count(&counter));
+
+    b.clear();
+    assert_eq!(0, b.len());
+    assert_eq!(1, std::rc::Rc::strong_count(&counter));
+
+    b.push(counter.clone());
+    assert_eq!(1, b.len());
+    assert_eq!(2, std::rc::Rc::strong_count(&counter));
+}
+
+#[test]
+fn shared_window_copies_only_on_write() {
+    let mut w = SharedWindow::new(3);
+    w.push(1);
+    w.push(2);
+
+    let mut snapshot = w.clone();
+    assert!(w.is_shared());
+    assert!(snapshot.is_shared());
+
+    w.push(3);
+    w.push(4);
+    assert!(!w.is_shared());
+    assert!(!snapshot.is_shared());
+
+    assert_eq!("CircularBuffer(2, 3, 4)", format!("{}", *w));
+    assert_eq!("CircularBuffer(1, 2)", format!("{}", *snapshot));
+
+    let v: Vec<_> = snapshot.make_mut().collect();
+    assert_eq!(vec![1, 2], v);
+    assert_eq!(3, w.len());
+}
+
+#[test]
+fn truncate_keeps_the_newest_elements() {
+    let mut b = CircularBuffer::new(4);
+    for i in 0..6 {
+        b.push(i);
+    }
+    b.truncate(5);
+    assert_eq!("CircularBuffer(2, 3, 4, 5)", format!("{}", b));
+    b.truncate(3);
+    assert_eq!("CircularBuffer(3, 4, 5)", format!("{}", b));
+    b.truncate(0);
+    assert_eq!(0, b.len());
+    b.push(6);
+    assert_eq!("CircularBuffer(6)", format!("{}", b));
+}
+
+#[test]
+fn truncate_drops_the_elements() {
+    let counter = std::rc::Rc::new(());
+    let mut b = CircularBuffer::new(3);
+    for _ in 0..4 {
+        b.push(counter.clone());
+    }
+    b.truncate(1);
+    assert_eq!(1, b.len());
+    assert_eq!(2, std::rc::Rc::strong_count(&counter));
+}
+
+#[test]
+fn retain_keeps_the_order() {
+    let mut b = CircularBuffer::new(5);
+    for i in 0..8 {
+        b.push(i);
+    }
+    b.retain(|i| i % 2 == 0);
+    assert_eq!("CircularBuffer(4, 6)", format!("{}", b));
+    b.push(8);
+    b.push(9);
+    b.push(10);
+    b.push(11);
+    assert_eq!("CircularBuffer(6, 8, 9, 10, 11)", format!("{}", b));
+    b.retain(|_| false);
+    assert_eq!(0, b.len());
+}
+
+proptest! {
+    #[test]
+    fn retain_behaves_like_vec_retain(
+        size in 1..50usize,
+        to_add in proptest::collection::vec(0..1000u32, 0..100),
+        to_remove in 0..50usize,
+        divisor in 1..5u32,
+        ) {
+        let mut b = CircularBuffer::<u32>::new(size);
+        for i in &to_add {
+            b.push(*i);
+        }
+        b.fill(&mut Vec::with_capacity(to_remove));
+        let kept = std::cmp::min(size, to_add.len());
+        let mut v = to_add[to_add.len() - kept..].to_vec();
+        v.drain(..std::cmp::min(to_remove, kept));
+
+        b.retain(|i| i % divisor == 0);
+        v.retain(|i| i % divisor == 0);
+
+        assert_eq!(v.len(), b.len());
+        let b: Vec<u32> = b.collect();
+        assert_eq!(v, b);
+    }
+}
+
+#[test]
+fn recorder_journal_has_sequence_numbers() {
+    let mut recorder = Recorder::new(CircularBuffer::new(2), Vec::new());
+    recorder.push(1);
+    assert_eq!(Some(1), recorder.pop());
+    assert_eq!(None, recorder.pop());
+    recorder.clear();
+    let journal = recorder.sink();
+    assert_eq!(
+        &vec![
+            Entry {
+                sequence: 0,
+                operation: Operation::Push(1)
+            },
+            Entry {
+                sequence: 1,
+                operation: Operation::Pop
+            },
+            Entry {
+                sequence: 2,
+                operation: Operation::Clear
+            },
+        ],
+        journal
+    );
+}
+
+proptest! {
+    #[test]
+    fn replay_reconstructs_the_buffer(
+        size in 1..20usize,
+        operations in proptest::collection::vec((0..1000u32, 0..4u8), 0..100),
+        ) {
+        let mut recorder = Recorder::new(CircularBuffer::new(size), Vec::new());
+        for (value, op) in operations {
+            match op {
+                0 => { recorder.pop(); }
+                1 if value % 10 == 0 => recorder.clear(),
+                _ => { recorder.push(value); }
+            }
+        }
+        let (buffer, journal) = recorder.into_parts();
+        let mut replayed = CircularBuffer::new(size);
+        replayed.replay(journal);
+        assert_eq!(buffer.len(), replayed.len());
+        let buffer: Vec<u32> = buffer.collect();
+        let replayed: Vec<u32> = replayed.collect();
+        assert_eq!(buffer, replayed);
+    }
+}
+
+#[test]
+fn drain_dropped_early_drops_the_rest_of_the_range() {
+    let counter = std::rc::Rc::new(());
+    let mut b = CircularBuffer::new(4);
+    for _ in 0..6 {
+        b.push(counter.clone());
+    }
+    {
+        let mut drain = b.drain(1..3);
+        assert_eq!(2, drain.len());
+        drain.next();
+    }
+    assert_eq!(2, b.len());
+    assert_eq!(3, std::rc::Rc::strong_count(&counter));
+}
+
+#[test]
+fn drain_leaked_leaves_the_buffer_consistent() {
+    let mut b = CircularBuffer::new(4);
+    for i in 0..6 {
+        b.push(i);
+    }
+    std::mem::forget(b.drain(1..2));
+    assert_eq!("CircularBuffer(2)", format!("{}", b));
+    b.push(6);
+    assert_eq!("CircularBuffer(2, 6)", format!("{}", b));
+}
+
+proptest! {
+    #[test]
+    fn drain_behaves_like_vec_drain(
+        size in 1..50usize,
+        to_add in proptest::collection::vec(0..1000u32, 0..100),
+        to_remove in 0..50usize,
+        a in 0..50usize,
+        b in 0..50usize,
+        from_back in proptest::bool::ANY,
+        ) {
+        let mut buffer = CircularBuffer::<u32>::new(size);
+        for i in &to_add {
+            buffer.push(*i);
+        }
+        buffer.fill(&mut Vec::with_capacity(to_remove));
+        let kept = std::cmp::min(size, to_add.len());
+        let mut v = to_add[to_add.len() - kept..].to_vec();
+        v.drain(..std::cmp::min(to_remove, kept));
+
+        let end = std::cmp::min(std::cmp::max(a, b), v.len());
+        let start = std::cmp::min(std::cmp::min(a, b), end);
+
+        let (drained, v_drained): (Vec<u32>, Vec<u32>) = if from_back {
+            (buffer.drain(start..end).rev().collect(), v.drain(start..end).rev().collect())
+        } else {
+            (buffer.drain(start..end).collect(), v.drain(start..end).collect())
+        };
+        assert_eq!(v_drained, drained);
+        assert_eq!(v.len(), buffer.len());
+        let buffer: Vec<u32> = buffer.collect();
+        assert_eq!(v, buffer);
+    }
+}
+
+#[test]
+fn reject_policy_keeps_the_first_elements() {
+    let mut b = CircularBuffer::with_policy(3, Reject);
+    for i in 0..10 {
+        b.push(i);
+    }
+    assert_eq!("CircularBuffer(0, 1, 2)", format!("{}", b));
+    assert_eq!(Some(0), b.next());
+    b.push(10);
+    assert_eq!("CircularBuffer(1, 2, 10)", format!("{}", b));
+}
+
+#[test]
+fn priority_policy_keeps_the_greatest_elements() {
+    let mut b = CircularBuffer::with_policy(3, Priority);
+    for i in [5, 1, 7, 3, 9, 2, 8] {
+        b.push(i);
+    }
+    assert_eq!("CircularBuffer(7, 9, 8)", format!("{}", b));
+}
+
+#[test]
+fn random_policy_is_deterministic() {
+    let mut a = CircularBuffer::with_policy(4, Random::new(42));
+    let mut b = CircularBuffer::with_policy(4, Random::new(42));
+    for i in 0..100 {
+        a.push(i);
+        b.push(i);
+        assert!(a.len() <= 4);
+    }
+    assert_eq!(format!("{}", a), format!("{}", b));
+    // the newest element is always pushed
+    assert_eq!(Some(99), a.last());
+}
+
+proptest! {
+    #[test]
+    fn random_policy_keeps_the_order_of_the_elements(
+        size in 1..20usize,
+        seed in 0..1000u64,
+        n in 0..100u32,
+        ) {
+        let mut b = CircularBuffer::with_policy(size, Random::new(seed));
+        for i in 0..n {
+            b.push(i);
+        }
+        let v: Vec<u32> = b.collect();
+        assert_eq!(std::cmp::min(size, n as usize), v.len());
+        assert!(v.windows(2).all(|w| w[0] < w[1]));
+    }
+}
+
+#[test]
+fn extract_if_dropped_early_keeps_the_rest() {
+    let mut b = CircularBuffer::new(6);
+    for i in 0..9 {
+        b.push(i);
+    }
+    let mut extracted = b.extract_if(|i| *i % 2 == 0);
+    assert_eq!(Some(4), extracted.next());
+    drop(extracted);
+    assert_eq!("CircularBuffer(3, 5, 6, 7, 8)", format!("{}", b));
+}
+
+proptest! {
+    #[test]
+    fn extract_if_splits_the_elements(
+        size in 1..50usize,
+        to_add in proptest::collection::vec(0..1000u32, 0..100),
+        to_remove in 0..50usize,
+        divisor in 1..5u32,
+        ) {
+        let mut b = CircularBuffer::<u32>::new(size);
+        for i in &to_add {
+            b.push(*i);
+        }
+        b.fill(&mut Vec::with_capacity(to_remove));
+        let kept = std::cmp::min(size, to_add.len());
+        let mut v = to_add[to_add.len() - kept..].to_vec();
+        v.drain(..std::cmp::min(to_remove, kept));
+
+        let extracted: Vec<u32> = b.extract_if(|i| *i % divisor == 0).collect();
+        let (v_extracted, v_left): (Vec<u32>, Vec<u32>) = v.into_iter().partition(|i| i % divisor == 0);
+
+        assert_eq!(v_extracted, extracted);
+        let left: Vec<u32> = b.collect();
+        assert_eq!(v_left, left);
+    }
+}
+
+#[test]
+fn double_buffer_swap_drops_the_elements_not_drained() {
+    let counter = std::rc::Rc::new(());
+    let mut frames = DoubleBuffer::new(3);
+    frames.push(counter.clone());
+    frames.push(counter.clone());
+    frames.swap();
+    assert_eq!(2, frames.front().len());
+    assert_eq!(0, frames.back().len());
+
+    frames.front_mut().next();
+    frames.push(counter.clone());
+    frames.swap();
+    assert_eq!(1, frames.front().len());
+    assert_eq!(0, frames.back().len());
+    assert_eq!(2, std::rc::Rc::strong_count(&counter));
+}
+
+#[test]
+fn split_double_buffer_hands_frames_between_threads() {
+    let (mut back, mut front) = DoubleBuffer::new(4).split();
+    assert!(!front.swap());
+
+    // a frame not taken is replaced by the newer one
+    back.push("a".to_string());
+    back.publish();
+    back.push("b".to_string());
+    back.push("c".to_string());
+    back.publish();
+    assert!(back.buffer().is_empty());
+    assert!(front.swap());
+    assert_eq!(Some("b".to_string()), front.pop());
+
+    let (mut back, mut front) = DoubleBuffer::new(4).split();
+    let producer = std::thread::spawn(move || {
+        for frame in 0..100u32 {
+            for i in 0..4 {
+                back.push(frame * 4 + i);
+            }
+            back.publish();
+        }
+        back
+    });
+    let mut last = None;
+    while last != Some(399) {
+        if front.swap() {
+            let frame: Vec<_> = front.buffer_mut().collect();
+            // every frame is whole, and newer than the previous one
+            assert_eq!(4, frame.len());
+            assert!(frame.windows(2).all(|w| w[1] == w[0] + 1));
+            assert!(last.is_none_or(|l| frame[0] > l));
+            last = frame.last().copied();
+        }
+    }
+    let back = producer.join().unwrap();
+    assert!(back.buffer().is_empty());
+}
+
+#[test]
+fn advance_read_discards_the_oldest_elements() {
+    let mut b = CircularBuffer::new(4);
+    for i in 0..6 {
+        b.push(i);
+    }
+    assert_eq!(3, b.advance_read(3));
+    assert_eq!("CircularBuffer(5)", format!("{}", b));
+    b.push(6);
+    b.push(7);
+    assert_eq!(3, b.advance_read(10));
+    assert_eq!(0, b.len());
+    assert_eq!(0, b.advance_read(1));
+}
+
+#[test]
+fn advance_read_drops_the_discarded_elements() {
+    let counter = std::rc::Rc::new(());
+    let mut b = CircularBuffer::new(3);
+    for _ in 0..5 {
+        b.push(counter.clone());
+    }
+    b.advance_read(2);
+    assert_eq!(2, std::rc::Rc::strong_count(&counter));
+}
+
+#[cfg(feature = "dsp")]
+#[test]
+fn spectrum_into_uses_the_newest_samples_across_the_wrap() {
+    use rustfft::num_complex::Complex;
+
+    let mut b = CircularBuffer::new(5);
+    for i in 0..7 {
+        b.push(i as f64);
+    }
+    let mut scratch = SpectrumScratch::new(Window::Rectangular);
+    let mut out = vec![Complex::new(0.0, 0.0); 4];
+    assert_eq!(4, b.spectrum_into(&mut scratch, &mut out));
+    // the DC component is the sum of the newest 4 samples
+    assert!((out[0].re - (3.0 + 4.0 + 5.0 + 6.0)).abs() < 1e-9);
+
+    // zero padding when there are not enough samples
+    let mut out = vec![Complex::new(0.0, 0.0); 8];
+    assert_eq!(5, b.spectrum_into(&mut scratch, &mut out));
+    assert!((out[0].re - (2.0 + 3.0 + 4.0 + 5.0 + 6.0)).abs() < 1e-9);
+
+    let mut scratch = SpectrumScratch::new(Window::Hann);
+    let mut out = vec![Complex::new(0.0, 0.0); 3];
+    b.spectrum_into(&mut scratch, &mut out);
+    // hann of length 3 is [0, 1, 0]
+    assert!((out[0].re - 5.0).abs() < 1e-9);
+}
+
+#[test]
+fn extend_from_slice_longer_than_the_buffer() {
+    let mut b = CircularBuffer::new(3);
+    b.push(0);
+    assert_eq!(0, b.extend_from_slice(&[1, 2, 3, 4, 5]));
+    assert_eq!("CircularBuffer(3, 4, 5)", format!("{}", b));
+    assert_eq!(0, b.extend_from_slice(&[]));
+}
+
+#[test]
+fn extend_from_slice_follows_the_policy() {
+    let mut b = CircularBuffer::with_policy(3, Reject);
+    assert_eq!(1, b.extend_from_slice(&[1, 2]));
+    assert_eq!(0, b.extend_from_slice(&[3, 4, 5]));
+    assert_eq!("CircularBuffer(1, 2, 3)", format!("{}", b));
+}
+
+proptest! {
+    #[test]
+    fn extend_from_slice_behaves_like_push(
+        size in 1..50usize,
+        matrix in proptest::collection::vec(
+            (proptest::collection::vec(0..1000u32, 0..100), 0..100usize),
+            0..50)
+        ) {
+        let mut a = CircularBuffer::<u32>::new(size);
+        let mut b = CircularBuffer::<u32>::new(size);
+        for (to_add, to_remove) in matrix {
+            let mut a_empty_spots = a.free_space();
+            for i in &to_add {
+                a_empty_spots = a.push(*i);
+            }
+            let b_empty_spots = b.extend_from_slice(&to_add);
+            assert_eq!(a_empty_spots, b_empty_spots);
+            assert_eq!(a.len(), b.len());
+
+            let mut a_drainer = Vec::with_capacity(to_remove);
+            let mut b_drainer = Vec::with_capacity(to_remove);
+            a.fill(&mut a_drainer);
+            b.fill(&mut b_drainer);
+            assert_eq!(a_drainer, b_drainer);
+        }
+    }
+}
+
+#[test]
+fn extend_pushes_all_the_elements() {
+    let mut b = CircularBuffer::new(3);
+    b.extend(vec![String::from("a"), String::from("b")]);
+    b.extend((0..3).map(|i| i.to_string()));
+    assert_eq!("CircularBuffer(0, 1, 2)", format!("{}", b));
+
+    let mut c = CircularBuffer::<u32>::new(3);
+    c.extend(&[1, 2, 3, 4]);
+    assert_eq!("CircularBuffer(2, 3, 4)", format!("{}", c));
+}
+
+#[test]
+fn iter_pairs_on_short_buffers() {
+    let mut b = CircularBuffer::new(3);
+    assert_eq!(0, b.iter_pairs().count());
+    b.push(1);
+    assert_eq!(0, b.iter_pairs().len());
+    b.push(2);
+    assert_eq!(vec![(&1, &2)], b.iter_pairs().collect::<Vec<_>>());
+}
+
+proptest! {
+    #[test]
+    fn iter_pairs_behaves_like_windows(
+        size in 1..50usize,
+        to_add in proptest::collection::vec(0..1000u32, 0..100),
+        to_remove in 0..50usize,
+        ) {
+        let mut b = CircularBuffer::<u32>::new(size);
+        for i in &to_add {
+            b.push(*i);
+        }
+        b.fill(&mut Vec::with_capacity(to_remove));
+        let kept = std::cmp::min(size, to_add.len());
+        let mut v = to_add[to_add.len() - kept..].to_vec();
+        v.drain(..std::cmp::min(to_remove, kept));
+
+        let pairs: Vec<(u32, u32)> = b.iter_pairs().map(|(a, b)| (*a, *b)).collect();
+        let v_pairs: Vec<(u32, u32)> = v.windows(2).map(|w| (w[0], w[1])).collect();
+        assert_eq!(v_pairs.len(), b.iter_pairs().len());
+        assert_eq!(v_pairs, pairs);
+    }
+}
+
+#[test]
+fn delta_buffer_deltas_and_cumsum() {
+    let mut b = DeltaBuffer::new(3);
+    assert_eq!(0, b.deltas().len());
+    assert_eq!(0, b.cumsum().count());
+    for i in [1.0, 2.0, 4.0, 7.0] {
+        b.push(i);
+    }
+    assert_eq!(vec![2.0, 3.0], b.deltas().collect::<Vec<_>>());
+    assert_eq!(vec![2.0, 6.0, 13.0], b.cumsum().collect::<Vec<_>>());
+    assert_eq!(3, b.buffer().len());
+}
+
+#[test]
+fn from_vec_and_collect_start_full() {
+    let b = CircularBuffer::from(vec![String::from("a"), String::from("b")]);
+    assert_eq!(2, b.len());
+    assert_eq!("CircularBuffer(a, b)", format!("{}", b));
+
+    let mut c: CircularBuffer<u32> = (0..4).collect();
+    assert_eq!(4, c.len());
+    assert_eq!(0, c.push(4));
+    assert_eq!("CircularBuffer(1, 2, 3, 4)", format!("{}", c));
+
+    let empty: CircularBuffer<u32> = std::iter::empty().collect();
+    assert_eq!(0, empty.len());
+}
+
+#[test]
+fn drain_into_ignores_the_capacity_of_the_sink() {
+    let mut b = CircularBuffer::new(4);
+    for i in 0..6 {
+        b.push(i);
+    }
+    let mut v = Vec::new();
+    assert_eq!(2, b.drain_into(&mut v, 2));
+    assert_eq!(vec![2, 3], v);
+    assert_eq!(2, b.drain_into(&mut v, 10));
+    assert_eq!(vec![2, 3, 4, 5], v);
+    assert_eq!(0, b.drain_into(&mut v, 10));
+    assert_eq!(0, b.len());
+}
+
+#[test]
+fn fill_n_ignores_the_spare_capacity() {
+    let mut b = CircularBuffer::new(4);
+    for i in 0..6 {
+        b.push(i);
+    }
+    let mut v = Vec::with_capacity(100);
+    assert_eq!(1, b.fill_n(&mut v, 1));
+    assert_eq!(vec![2], v);
+    let mut v = Vec::new();
+    assert_eq!(0, b.fill_n(&mut v, 0));
+    assert_eq!(0, v.capacity());
+    assert_eq!(3, b.fill_n(&mut v, 10));
+    assert_eq!(vec![3, 4, 5], v);
+    assert!(b.is_empty());
+}
+
+#[test]
+fn fill_while_stops_before_the_first_rejected_element() {
+    let mut b = CircularBuffer::new(4);
+    for i in 0..6 {
+        b.push(i);
+    }
+    let mut v = Vec::new();
+    assert_eq!(0, b.fill_while(&mut v, |e| *e > 2));
+    assert!(v.is_empty());
+    assert_eq!(2, b.fill_while(&mut v, |e| e % 4 != 0));
+    assert_eq!(vec![2, 3], v);
+    let mut seen = Vec::new();
+    assert_eq!(2, b.fill_while(&mut v, |e| {
+        seen.push(*e);
+        true
+    }));
+    assert_eq!(vec![4, 5], seen);
+    assert_eq!(vec![2, 3, 4, 5], v);
+    assert!(b.is_empty());
+}
+
+#[test]
+fn move_to_matches_popping_and_pushing() {
+    for src_size in 0..5 {
+        for dst_size in 0..5 {
+            for n in 0..7 {
+                let mut src = CircularBuffer::new(src_size);
+                let mut dst = CircularBuffer::new(dst_size);
+                for i in 0..src_size + 2 {
+                    src.push(i.to_string());
+                    dst.push((i * 10).to_string());
+                }
+                src.next();
+                let mut expected_src = src.clone();
+                let mut expected_dst = dst.clone();
+                let mut expected = 0;
+                for e in expected_src.by_ref().take(n) {
+                    expected_dst.push(e);
+                    expected += 1;
+                }
+                assert_eq!(expected, src.move_to(&mut dst, n));
+                assert_eq!(expected_src, src);
+                assert_eq!(expected_dst, dst);
+            }
+        }
+    }
+
+    let mut src = circular_buffer![1, 2, 3];
+    let mut rejecting = CircularBuffer::with_policy(2, Reject);
+    assert_eq!(3, src.move_to(&mut rejecting, 5));
+    assert_eq!(rejecting, [1, 2]);
+    let mut src = circular_buffer![1, 2, 3];
+    let mut growable = CircularBuffer::growable(1);
+    assert_eq!(2, src.move_to(&mut growable, 2));
+    assert_eq!(growable, [1, 2]);
+}
+
+#[test]
+fn copy_to_slice_keeps_the_elements() {
+    let mut b = CircularBuffer::new(4);
+    for i in 0..6u8 {
+        b.push(i);
+    }
+    let mut out = [9; 3];
+    assert_eq!(3, b.copy_to_slice(&mut out));
+    assert_eq!([2, 3, 4], out);
+    let mut out = [9; 5];
+    assert_eq!(4, b.copy_to_slice(&mut out));
+    assert_eq!([2, 3, 4, 5, 9], out);
+    assert_eq!(b, [2, 3, 4, 5]);
+    b.push(6);
+    assert_eq!(4, b.copy_to_slice(&mut out));
+    assert_eq!([3, 4, 5, 6, 9], out);
+    assert_eq!(0, CircularBuffer::<u8>::new(2).copy_to_slice(&mut out));
+}
+
+#[test]
+fn search_helpers_do_not_consume() {
+    let mut b = CircularBuffer::new(4);
+    for word in ["a", "b", "c", "d", "e", "f"] {
+        b.push(word.to_string());
+    }
+    assert!(b.contains(&"f".to_string()));
+    assert!(b.contains(&"c".to_string()));
+    assert!(!b.contains(&"b".to_string()));
+    assert_eq!(Some(2), b.position(|w| w == "e"));
+    assert_eq!(None, b.position(|w| w == "a"));
+    assert_eq!(Some(&"d".to_string()), b.find(|w| w.as_str() > "c"));
+    assert_eq!(None, b.find(|w| w.is_empty()));
+    assert_eq!(4, b.len());
+
+    let empty: CircularBuffer<u32> = CircularBuffer::new(0);
+    assert!(!empty.contains(&0));
+    assert_eq!(None, empty.position(|_| true));
+}
+
+#[test]
+fn binary_search_across_the_wrap_around() {
+    for pushed in 0..12 {
+        let mut b = CircularBuffer::new(6);
+        for i in 0..pushed {
+            b.push(i * 2);
+        }
+        let v = b.to_vec();
+        for x in -1..(2 * pushed + 1) {
+            assert_eq!(v.binary_search(&x), b.binary_search(&x), "{} in {:?}", x, v);
+            assert_eq!(v.partition_point(|e| *e < x), b.partition_point(|e| *e < x));
+            assert_eq!(
+                v.binary_search_by_key(&(x * 3), |e| e * 3),
+                b.binary_search_by_key(&(x * 3), |e| e * 3)
+            );
+        }
+    }
+}
+
+#[test]
+fn sort_unstable_keeps_the_buffer_usable() {
+    let mut b = CircularBuffer::new(5);
+    for i in [4, 9, 1, 8, 2, 7, 3] {
+        b.push(i);
+    }
+    b.next();
+    b.sort_unstable();
+    assert_eq!(b, [2, 3, 7, 8]);
+    b.push(0);
+    b.push(5);
+    assert_eq!(b, [3, 7, 8, 0, 5]);
+    b.sort_unstable_by(|a, b| b.cmp(a));
+    assert_eq!(b, [8, 7, 5, 3, 0]);
+    b.sort_unstable_by_key(|e| (e % 2, *e));
+    assert_eq!(b, [0, 8, 3, 5, 7]);
+}
+
+#[test]
+fn rotate_matches_vec_deque() {
+    use std::collections::VecDeque;
+
+    for size in 1..6 {
+        for pushed in 0..2 * size {
+            let mut b = CircularBuffer::new(size);
+            for i in 0..pushed {
+                b.push(i.to_string());
+            }
+            let expected: VecDeque<String> = b.iter().cloned().collect();
+            for n in 0..=b.len() {
+                let mut left = b.clone();
+                let mut deque = expected.clone();
+                left.rotate_left(n);
+                deque.rotate_left(n);
+                assert_eq!(left, Vec::from(deque.clone()));
+                left.rotate_right(n);
+                deque.rotate_right(n);
+                assert_eq!(left, Vec::from(deque));
+                left.push("new".to_string());
+                assert_eq!(Some(&"new".to_string()), left.back());
+            }
+        }
+    }
+}
+
+#[test]
+fn dedup_matches_vec_across_the_wrap_around() {
+    let values = [1, 1, 2, 2, 2, 3, 1, 1, 4, 4];
+    for popped in 0..values.len() {
+        let mut b = CircularBuffer::new(7);
+        for value in values.iter().chain(values.iter()).take(values.len() + popped) {
+            b.push(value.to_string());
+        }
+        let mut expected = b.to_vec();
+        expected.dedup();
+        b.dedup();
+        assert_eq!(b, expected);
+        b.push("new".to_string());
+        assert_eq!(Some(&"new".to_string()), b.back());
+    }
+
+    let mut b = CircularBuffer::new(5);
+    b.extend_from_slice(&["a", "A", "b", "B", "c"]);
+    b.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    assert_eq!(b, ["a", "b", "c"]);
+}
+
+#[test]
+fn swap_uses_positions_from_the_oldest() {
+    let mut b = CircularBuffer::new(4);
+    for i in 0..6 {
+        b.push(i.to_string());
+    }
+    b.swap(0, 3);
+    assert_eq!(b, ["5", "3", "4", "2"]);
+    b.swap(1, 1);
+    b.swap(2, 1);
+    assert_eq!(b, ["5", "4", "3", "2"]);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| b.swap(0, 4)));
+    assert!(result.is_err());
+}
+
+#[test]
+fn replace_returns_the_previous_element() {
+    let mut b = CircularBuffer::new(3);
+    for i in 0..5 {
+        b.push(i.to_string());
+    }
+    assert_eq!("2", b.replace(0, "a".to_string()));
+    assert_eq!("4", b.replace(2, "c".to_string()));
+    assert_eq!(b, ["a", "3", "c"]);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        b.replace(3, "d".to_string());
+    }));
+    assert!(result.is_err());
+    assert_eq!(3, b.len());
+}
+
+#[test]
+fn insert_and_remove_match_vec() {
+    let mut b = CircularBuffer::new(6);
+    let mut v = Vec::new();
+    for i in 0..4 {
+        b.push(i.to_string());
+        v.push(i.to_string());
+    }
+    b.next();
+    v.remove(0);
+    for (index, value) in [(0, "a"), (3, "b"), (2, "c")] {
+        b.insert(index, value.to_string());
+        v.insert(index, value.to_string());
+        assert_eq!(b, v);
+    }
+    assert_eq!(Some(v.remove(4)), b.remove(4));
+    assert_eq!(Some(v.remove(1)), b.remove(1));
+    assert_eq!(None, b.remove(v.len()));
+    assert_eq!(b, v);
+
+    // a full buffer evicts the oldest element first
+    let mut full = CircularBuffer::new(3);
+    full.extend_from_slice(&[1, 2, 3]);
+    full.insert(0, 0);
+    assert_eq!(full, [0, 2, 3]);
+    full.insert(3, 4);
+    assert_eq!(full, [2, 3, 4]);
+
+    let mut rejecting = CircularBuffer::with_policy(2, Reject);
+    rejecting.push(1);
+    rejecting.push(2);
+    assert_eq!(0, rejecting.insert(1, 3));
+    assert_eq!(rejecting, [1, 2]);
+}
+
+#[test]
+fn swap_remove_keeps_the_other_elements() {
+    let mut b = CircularBuffer::new(4);
+    for i in 0..6 {
+        b.push(i.to_string());
+    }
+    assert_eq!(Some("5".to_string()), b.swap_remove_back(3));
+    assert_eq!(Some("3".to_string()), b.swap_remove_front(1));
+    assert_eq!(b, ["2", "4"]);
+    assert_eq!(None, b.swap_remove_front(2));
+    assert_eq!(None, b.swap_remove_back(2));
+    assert_eq!(Some("2".to_string()), b.swap_remove_back(0));
+    assert_eq!(Some("4".to_string()), b.swap_remove_front(0));
+    assert!(b.is_empty());
+    b.push("6".to_string());
+    assert_eq!(b, ["6"]);
+}
+
+#[test]
+fn splice_is_bounded_by_the_capacity() {
+    let mut b = CircularBuffer::new(6);
+    for i in 0..8 {
+        b.push(i.to_string());
+    }
+    // the replacement is shorter than the range
+    let removed: Vec<_> = b.splice(1..4, ["a".to_string()]).collect();
+    assert_eq!(removed, ["3", "4", "5"]);
+    assert_eq!(b, ["2", "a", "6", "7"]);
+
+    // longer, it fills the free space and leaves the rest in the iterator
+    let mut replacement = (0..5).map(|i| i.to_string());
+    b.splice(1..2, replacement.by_ref());
+    assert_eq!(b, ["2", "0", "1", "2", "6", "7"]);
+    assert_eq!(Some("3".to_string()), replacement.next());
+
+    // not consuming the iterator still removes the range
+    drop(b.splice(4.., ["x".to_string()]));
+    assert_eq!(b, ["2", "0", "1", "2", "x"]);
+    b.splice(..0, ["y".to_string()]);
+    assert_eq!(b, ["y", "2", "0", "1", "2", "x"]);
+    assert!(b.is_full());
+}
+
+#[test]
+fn partition_keeps_the_order_and_the_capacity() {
+    let mut b = CircularBuffer::with_policy(5, Reject);
+    for i in 0..8 {
+        b.push(i.to_string());
+    }
+    let (even, odd) = b.partition(|s| s.parse::<u32>().unwrap() % 2 == 0);
+    assert_eq!(even, ["0", "2", "4"]);
+    assert_eq!(odd, ["1", "3"]);
+    assert_eq!((5, 5), (even.capacity(), odd.capacity()));
+
+    let b = CircularBuffer::from_last(3, 0..10);
+    let (all, none): (Vec<_>, Vec<_>) = Iterator::partition(b, |_| true);
+    assert_eq!((vec![7, 8, 9], vec![]), (all, none));
+}
+
+#[test]
+fn split_off_on_both_sides_of_the_wrap() {
+    for at in 0..=4 {
+        let mut b = CircularBuffer::new(5);
+        for i in 0..7 {
+            b.push(i.to_string());
+        }
+        b.next();
+        let mut v: Vec<_> = b.iter().cloned().collect();
+        let other = b.split_off(at);
+        assert_eq!(other, v.split_off(at));
+        assert_eq!(b, v);
+        assert_eq!(5, other.capacity());
+        b.extend(other);
+        assert_eq!(4, b.len());
+    }
+}
+
+#[test]
+fn append_empties_the_other_buffer() {
+    let mut global = CircularBuffer::new(5);
+    let mut per_thread = CircularBuffer::with_policy(3, Reject);
+    for i in 0..4 {
+        global.push(i.to_string());
+        per_thread.push((10 + i).to_string());
+    }
+    assert_eq!(0, global.append(&mut per_thread));
+    assert_eq!(global, ["2", "3", "10", "11", "12"]);
+    assert!(per_thread.is_empty());
+
+    let mut priority = CircularBuffer::with_policy(2, Priority);
+    priority.push(5);
+    let mut other = CircularBuffer::new(3);
+    other.extend_from_slice(&[1, 9, 7]);
+    priority.append(&mut other);
+    assert_eq!(priority, [9, 7]);
+    assert!(other.is_empty());
+}
+
+#[test]
+fn clone_from_reuses_the_allocation() {
+    let mut source = CircularBuffer::new(4);
+    for i in 0..6 {
+        source.push(i.to_string());
+    }
+    let mut copy = CircularBuffer::new(4);
+    copy.push("x".to_string());
+    let allocation = copy.buffer;
+    copy.clone_from(&source);
+    assert_eq!(copy, source);
+    assert_eq!(allocation, copy.buffer);
+    assert!(copy.is_full());
+
+    let mut smaller = CircularBuffer::new(2);
+    smaller.clone_from(&source);
+    assert_eq!(smaller, source);
+    assert_eq!(4, smaller.capacity());
+}
+
+#[test]
+fn duplicate_copy_is_the_same_as_clone() {
+    let mut b = CircularBuffer::growable_up_to(2, 8);
+    for i in 0..5u64 {
+        b.push(i);
+    }
+    b.next();
+    let copy = b.duplicate_copy();
+    let clone = b.clone();
+    assert_eq!(copy, clone);
+    assert_eq!(copy.as_slices(), clone.as_slices());
+    assert_eq!(copy.growth_limit, Some(8));
+
+    let empty: CircularBuffer<u8> = CircularBuffer::new(0);
+    assert!(empty.duplicate_copy().is_empty());
+}
+
+#[test]
+fn on_evict_receives_every_element_lost() {
+    let mut lost = Vec::new();
+    {
+        let mut b = CircularBuffer::with_policy(3, OnEvict::new(|e: u32| lost.push(e)));
+        b.extend_from_slice(&[1, 2, 3, 4, 5]);
+        b.insert(0, 0);
+        assert_eq!(b, [0, 4, 5]);
+    }
+    assert_eq!(vec![1, 2, 3], lost);
+
+    let mut rejected = 0;
+    {
+        let policy = OnEvict::with_policy(Priority, |_: u32| rejected += 1);
+        let mut b = CircularBuffer::with_policy(2, policy);
+        for i in [5, 1, 7, 3] {
+            b.push(i);
+        }
+        assert_eq!(b, [5, 7]);
+    }
+    assert_eq!(2, rejected);
+
+    let mut dropped = 0;
+    CircularBuffer::with_policy(0, OnEvict::new(|_: u32| dropped += 1)).push(1);
+    assert_eq!(1, dropped);
+}
+
+#[test]
+fn instrumented_counts_the_elements_lost() {
+    let mut b = Instrumented::new(CircularBuffer::with_policy(2, Priority));
+    for i in [5, 1, 7, 3, 9] {
+        b.push(i);
+    }
+    // 1 and 5 are evicted, 3 is rejected
+    assert_eq!(3, b.overwritten_count());
+    assert_eq!(5, b.total_pushed());
+    while b.pop().is_some() {}
+    assert_eq!(2, b.total_popped());
+
+    let mut growable = Instrumented::new(CircularBuffer::growable_up_to(1, 2));
+    for i in 0..3 {
+        growable.push(i);
+    }
+    assert_eq!(1, growable.overwritten_count());
+    assert_eq!(growable.into_inner(), [1, 2]);
+}
+
+#[test]
+fn instrumented_counts_the_bulk_operations() {
+    let mut b = Instrumented::new(CircularBuffer::new(4));
+    b.extend_from_slice(&[1, 2]);
+    b.extend(3..6);
+    assert_eq!(5, b.total_pushed());
+    assert_eq!(1, b.overwritten_count());
+    assert_eq!(4, b.high_watermark());
+
+    assert_eq!(vec![2, 3], b.by_ref().take(2).collect::<Vec<_>>());
+    assert_eq!(2, b.total_popped());
+    b.extend_from_slice(&[6, 7, 8, 9]);
+    assert_eq!(9, b.total_pushed());
+    assert_eq!(3, b.overwritten_count());
+    assert_eq!(vec![6, 7, 8, 9], b.by_ref().collect::<Vec<_>>());
+    assert_eq!(6, b.total_popped());
+}
+
+#[test]
+fn high_watermark_is_the_highest_len() {
+    let mut b = Instrumented::new(CircularBuffer::from_last(8, 0..2));
+    assert_eq!(2, b.high_watermark());
+    for i in 0..4 {
+        b.push(i);
+    }
+    b.pop();
+    b.pop();
+    b.push(4);
+    assert_eq!(6, b.high_watermark());
+    b.reset_stats();
+    assert_eq!(5, b.high_watermark());
+}
+
+#[test]
+fn comparisons_do_not_consume_the_buffers() {
+    let mut a = CircularBuffer::new(3);
+    a.extend_from_slice(&[1, 2, 3, 4]);
+    let b = circular_buffer![2, 3, 5];
+    assert!(a != b);
+    assert!(a == a.clone());
+    assert!(a < b);
+    assert!(b >= a);
+    assert_eq!(Some(core::cmp::Ordering::Less), PartialOrd::partial_cmp(&a, &b));
+    assert_eq!(core::cmp::Ordering::Greater, Ord::cmp(&b, &a));
+    assert!(PartialEq::eq(&a, &a.clone()));
+    assert_eq!(a, [2, 3, 4]);
+    assert_eq!(b, [2, 3, 5]);
+}
+
+#[test]
+fn drain_into_any_extend_sink() {
+    use std::collections::{BinaryHeap, VecDeque};
+
+    struct Sum(u32);
+    impl Extend<u32> for Sum {
+        fn extend<I: IntoIterator<Item = u32>>(&mut self, iter: I) {
+            self.0 += iter.into_iter().sum::<u32>();
+        }
+    }
+
+    let mut b = CircularBuffer::new(8);
+    b.extend_from_slice(&[5, 1, 4, 2, 3, 6]);
+    let mut heap = BinaryHeap::new();
+    assert_eq!(3, b.drain_into(&mut heap, 3));
+    assert_eq!(vec![5, 4, 1], heap.into_sorted_vec().into_iter().rev().collect::<Vec<_>>());
+    let mut deque = VecDeque::from(vec![0]);
+    assert_eq!(1, b.drain_into(&mut deque, 1));
+    assert_eq!(vec![0, 2], Vec::from(deque));
+    let mut sum = Sum(0);
+    assert_eq!(2, b.drain_into(&mut sum, usize::MAX));
+    assert_eq!(9, sum.0);
+}
+
+#[test]
+fn from_array_and_try_from_slice() {
+    use std::convert::TryFrom;
+
+    let b = CircularBuffer::from([1, 2, 3]);
+    assert_eq!("CircularBuffer(1, 2, 3)", format!("{}", b));
+
+    let names = [String::from("a"), String::from("b")];
+    let c = CircularBuffer::try_from(&names[..]).unwrap();
+    assert_eq!("CircularBuffer(a, b)", format!("{}", c));
+    assert_eq!(2, names.len());
+}
+
+#[test]
+fn alloc_error_reports_the_requested_capacity() {
+    let error = CircularBuffer::<u64>::try_with_policy(usize::MAX / 4, OldestFirst).unwrap_err();
+    assert!(error.is_capacity_overflow());
+    assert_eq!(usize::MAX / 4, error.capacity());
+    assert_eq!(8, error.element_size());
+    assert!(std::error::Error::source(&error).is_some());
+    assert!(format!("{}", error).contains("8 bytes"));
+}
+
+#[test]
+fn circular_buffer_macro() {
+    let b = circular_buffer![String::from("a"), String::from("b"),];
+    assert_eq!("CircularBuffer(a, b)", format!("{}", b));
+
+    let mut c = circular_buffer![0; 3];
+    assert_eq!(3, c.len());
+    c.push(1);
+    assert_eq!("CircularBuffer(0, 0, 1)", format!("{}", c));
+
+    let empty: CircularBuffer<u32> = circular_buffer![];
+    assert_eq!(0, empty.len());
+}
+
+#[test]
+fn capacity_and_fullness_accessors() {
+    let mut b = CircularBuffer::new(2);
+    assert_eq!(2, b.capacity());
+    assert!(b.is_empty());
+    assert!(!b.is_full());
+    assert_eq!(2, b.free_space());
+
+    b.push(1);
+    assert!(!b.is_empty());
+    assert!(!b.is_full());
+    assert_eq!(1, b.free_space());
+
+    b.push(2);
+    b.push(3);
+    assert!(b.is_full());
+    assert_eq!(0, b.free_space());
+    assert_eq!(2, b.capacity());
+
+    b.next();
+    assert!(!b.is_full());
+    assert_eq!(1, b.free_space());
+}
+
+#[test]
+fn sparkline_across_the_wrap() {
+    let mut b = CircularBuffer::new(4);
+    let mut line = String::new();
+    b.sparkline(&mut line).unwrap();
+    assert_eq!("", line);
+
+    for i in [9.0, 9.0, 1.0, 3.0, 5.0, 7.0] {
+        b.push(i);
+    }
+    b.sparkline(&mut line).unwrap();
+    assert_eq!("▁▃▆█", line);
+
+    let mut flat = CircularBuffer::<f32>::new(3);
+    flat.extend(&[2.0f32, 2.0, f32::NAN]);
+    let mut line = String::new();
+    flat.sparkline(&mut line).unwrap();
+    assert_eq!("▁▁ ", line);
+}
+
+#[test]
+fn resize_drops_the_oldest_elements() {
+    let counter = std::rc::Rc::new(());
+    let mut b = CircularBuffer::new(4);
+    for _ in 0..6 {
+        b.push(counter.clone());
+    }
+    b.resize(2);
+    assert_eq!(2, b.capacity());
+    assert!(b.is_full());
+    assert_eq!(3, std::rc::Rc::strong_count(&counter));
+
+    b.resize(0);
+    assert_eq!(0, b.len());
+    assert_eq!(1, std::rc::Rc::strong_count(&counter));
+}
+
+proptest! {
+    #[test]
+    fn resize_keeps_the_newest_elements(
+        size in 1..50usize,
+        new_size in 1..50usize,
+        to_add in proptest::collection::vec(0..1000u32, 0..100),
+        to_remove in 0..50usize,
+        after in proptest::collection::vec(0..1000u32, 0..100),
+        ) {
+        let mut b = CircularBuffer::<u32>::new(size);
+        b.extend(&to_add);
+        b.advance_read(to_remove);
+        let kept = std::cmp::min(size, to_add.len());
+        let mut v = to_add[to_add.len() - kept..].to_vec();
+        v.drain(..std::cmp::min(to_remove, kept));
+
+        b.resize(new_size);
+        if v.len() > new_size {
+            v.drain(..v.len() - new_size);
+        }
+        assert_eq!(v.len(), b.len());
+
+        b.extend(&after);
+        v.extend(&after);
+        if v.len() > new_size {
+            v.drain(..v.len() - new_size);
+        }
+        let b: Vec<u32> = b.collect();
+        assert_eq!(v, b);
+    }
+}
+
+#[test]
+fn growable_buffer_does_not_lose_elements() {
+    let mut b = CircularBuffer::growable(0);
+    assert_eq!(0, b.capacity());
+    b.extend_from_slice(&[1, 2, 3]);
+    assert_eq!(4, b.capacity());
+    b.next();
+    b.extend_from_slice(&[4, 5, 6]);
+    assert_eq!("CircularBuffer(2, 3, 4, 5, 6)", format!("{}", b));
+    assert_eq!(8, b.capacity());
+
+    let mut c = CircularBuffer::growable_up_to(1, 3);
+    c.extend_from_slice(&[1, 2, 3, 4]);
+    assert_eq!(3, c.capacity());
+    assert_eq!(0, c.free_space());
+    assert_eq!("CircularBuffer(2, 3, 4)", format!("{}", c));
+}
+
+#[test]
+fn peek_mut_on_wrapped_buffer() {
+    let mut b = CircularBuffer::new(2);
+    assert!(b.peek_mut().is_none());
+    b.push(String::from("a"));
+    b.push(String::from("b"));
+    b.push(String::from("c"));
+    {
+        let mut oldest = b.peek_mut().unwrap();
+        assert_eq!("b", *oldest);
+        oldest.push('!');
+    }
+    assert_eq!("CircularBuffer(b!, c)", format!("{}", b));
+    let oldest = b.peek_mut().unwrap();
+    assert_eq!("b!", PeekMut::pop(oldest));
+    let oldest = b.peek_mut().unwrap();
+    assert_eq!("c", PeekMut::pop(oldest));
+    assert!(b.peek_mut().is_none());
+}
+
+#[test]
+fn shrink_keeps_wrapped_elements() {
+    let mut b = CircularBuffer::new(5);
+    for i in 0..7 {
+        b.push(i);
+    }
+    b.next();
+    b.next();
+    b.shrink_to_fit();
+    assert_eq!(3, b.capacity());
+    assert!(b.is_full());
+    assert_eq!("CircularBuffer(4, 5, 6)", format!("{}", b));
+    b.shrink_to(10);
+    assert_eq!(3, b.capacity());
+    b.shrink_to(1);
+    assert_eq!(3, b.capacity());
+    b.clear();
+    b.shrink_to(1);
+    assert_eq!(1, b.capacity());
+    b.push(7);
+    b.push(8);
+    assert_eq!("CircularBuffer(8)", format!("{}", b));
+}
+
+#[test]
+fn shrink_to_fit_an_empty_buffer_frees_the_array() {
+    let mut b = CircularBuffer::new(5);
+    b.push(1);
+    b.next();
+    b.shrink_to_fit();
+    assert_eq!(0, b.capacity());
+    assert_eq!(0, b.push(2));
+    assert!(b.is_empty());
+
+    let mut growable = CircularBuffer::growable(4);
+    growable.shrink_to(0);
+    assert_eq!(0, growable.capacity());
+    growable.push(3);
+    assert_eq!(growable, [3]);
+}
+
+#[test]
+fn try_extend_from_slice_checks_capacity() {
+    let mut b = CircularBuffer::new(3);
+    b.push(0);
+    assert_eq!(Ok(0), b.try_extend_from_slice(&[1, 2, 3]));
+    assert_eq!("CircularBuffer(1, 2, 3)", format!("{}", b));
+    let e = b.try_extend_from_slice(&[4, 5, 6, 7]).unwrap_err();
+    assert_eq!(CapacityError::new(3, 4), e);
+    assert_eq!(3, e.capacity());
+    assert_eq!("CircularBuffer(1, 2, 3)", format!("{}", b));
+
+    let mut g = CircularBuffer::growable_up_to(1, 4);
+    assert_eq!(Ok(0), g.try_extend_from_slice(&[1, 2, 3, 4]));
+    assert!(g.try_extend_from_slice(&[1, 2, 3, 4, 5]).is_err());
+}
+
+#[test]
+fn drop_releases_the_elements() {
+    let counter = std::rc::Rc::new(());
+    {
+        let mut b = CircularBuffer::new(3);
+        for _ in 0..5 {
+            b.push(counter.clone());
+        }
+        b.next();
+        assert_eq!(3, std::rc::Rc::strong_count(&counter));
+    }
+    assert_eq!(1, std::rc::Rc::strong_count(&counter));
+    {
+        let mut b = CircularBuffer::new(0);
+        b.extend(std::iter::empty::<String>());
+    }
+}
+
+#[test]
+fn zero_capacity_buffer_holds_nothing() {
+    let mut b = CircularBuffer::try_new(0).unwrap();
+    assert_eq!(0, b.capacity());
+    assert_eq!(0, b.push(1));
+    assert_eq!(0, b.extend_from_slice(&[2, 3]));
+    b.extend(vec![4, 5]);
+    assert!(b.is_empty());
+    assert_eq!(None, b.next());
+    assert_eq!(0, b.advance_read(3));
+    b.truncate(0);
+    b.retain(|_| true);
+    assert_eq!(0, b.drain(..).count());
+    assert_eq!(0, b.extract_if(|_| true).count());
+    assert!(b.peek_mut().is_none());
+    let mut v = Vec::with_capacity(2);
+    assert_eq!(0, b.fill(&mut v));
+    assert_eq!(0, b.drain_into(&mut v, 2));
+    assert_eq!("CircularBuffer(<empty>)", format!("{}", b));
+    let c = b.clone();
+    assert_eq!(0, c.len());
+    b.resize(2);
+    b.push(6);
+    assert_eq!("CircularBuffer(6)", format!("{}", b));
+    b.resize(0);
+    assert!(b.is_empty());
+
+    let mut s = CircularBuffer::with_policy(0, Priority);
+    s.push(String::from("dropped"));
+    assert!(s.is_empty());
+}
+
+#[test]
+fn memory_usage_follows_capacity() {
+    let mut b: CircularBuffer<u32> = CircularBuffer::new(4);
+    let header = std::mem::size_of::<CircularBuffer<u32>>();
+    assert_eq!(0, b.len_bytes());
+    assert_eq!(16 + header, b.memory_usage());
+    b.extend_from_slice(&[1, 2, 3]);
+    assert_eq!(12, b.len_bytes());
+    b.resize(10);
+    assert_eq!(40 + header, b.memory_usage());
+    let z: CircularBuffer<()> = CircularBuffer::new(100);
+    assert_eq!(std::mem::size_of::<CircularBuffer<()>>(), z.memory_usage());
+}
+
+#[test]
+fn vecdeque_compatible_api() {
+    use std::collections::VecDeque;
+    let mut b = CircularBuffer::new(3);
+    let mut d = VecDeque::new();
+    assert_eq!(d.front(), b.front());
+    assert_eq!(d.back(), b.back());
+    for i in 0..5 {
+        b.push_back(i);
+        d.push_back(i);
+    }
+    // the buffer overwrote the oldest elements, the deque grew
+    d.pop_front();
+    d.pop_front();
+    assert_eq!(d.front(), b.front());
+    assert_eq!(d.back(), b.back());
+    assert!(d.iter().eq(b.iter()));
+    assert!(d.iter().rev().eq(b.iter().rev()));
+    let mut sum = 0;
+    for x in &b {
+        sum += x;
+    }
+    assert_eq!(2 + 3 + 4, sum);
+    assert_eq!(d.pop_front(), b.pop_front());
+    assert_eq!(d.len(), b.len());
+}
+
+#[test]
+fn zero_sized_types() {
+    let mut b: CircularBuffer<()> = CircularBuffer::new(usize::MAX);
+    assert_eq!(std::mem::size_of::<CircularBuffer<()>>(), b.memory_usage());
+    for _ in 0..1000 {
+        b.push(());
+    }
+    assert_eq!(1000, b.len());
+    assert_eq!(1000, b.iter().count());
+    assert_eq!(10, b.advance_read(10));
+    assert_eq!(Some(()), b.next());
+    assert_eq!(989, b.len());
+
+    let mut tokens = CircularBuffer::new(3);
+    for _ in 0..5 {
+        tokens.push(());
+    }
+    assert!(tokens.is_full());
+    assert_eq!(3, tokens.drain(..).count());
+    assert!(tokens.is_empty());
+}
+
+#[test]
+fn zero_sized_types_are_dropped() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    struct Token;
+    impl Drop for Token {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+    {
+        let mut b = CircularBuffer::new(3);
+        for _ in 0..5 {
+            b.push(Token);
+        }
+        assert_eq!(2, DROPS.load(Ordering::SeqCst));
+        b.resize(2);
+        assert_eq!(3, DROPS.load(Ordering::SeqCst));
+        b.next();
+        assert_eq!(4, DROPS.load(Ordering::SeqCst));
+    }
+    assert_eq!(5, DROPS.load(Ordering::SeqCst));
+}
+
+proptest! {
+    #[test]
+    fn window_reductions_match_the_elements(
+            size in 1..200usize,
+            v in proptest::collection::vec(proptest::num::i32::ANY, 0..600)) {
+        let mut b = CircularBuffer::new(size);
+        b.extend_from_slice(&v);
+        let window = &v[v.len().saturating_sub(size)..];
+        assert_eq!(window.iter().fold(0i32, |acc, x| acc.wrapping_add(*x)), b.window_sum());
+        assert_eq!(window.iter().copied().min(), b.window_min());
+        assert_eq!(window.iter().copied().max(), b.window_max());
+    }
+
+    #[test]
+    fn window_float_reductions_match_the_elements(
+            size in 1..200usize,
+            v in proptest::collection::vec(-1000i16..1000, 0..600)) {
+        let mut b = CircularBuffer::new(size);
+        for x in &v {
+            b.push(f64::from(*x));
+        }
+        let window = &v[v.len().saturating_sub(size)..];
+        // small integers are summed exactly in any order
+        assert_eq!(window.iter().map(|x| f64::from(*x)).sum::<f64>(), b.window_sum());
+        assert_eq!(window.iter().min().map(|x| f64::from(*x)), b.window_min());
+        assert_eq!(window.iter().max().map(|x| f64::from(*x)), b.window_max());
+    }
+}
+
+#[test]
+fn window_float_reductions_ignore_nan() {
+    let mut b = CircularBuffer::new(4);
+    b.extend_from_slice(&[f32::NAN, 2.0, f32::NAN, -1.0]);
+    assert_eq!(Some(-1.0), b.window_min());
+    assert_eq!(Some(2.0), b.window_max());
+    let empty: CircularBuffer<f32> = CircularBuffer::new(4);
+    assert_eq!(0.0, empty.window_sum());
+    assert_eq!(None, empty.window_min());
+}
+
+#[test]
+fn types_with_niches_round_trip() {
+    let mut b = CircularBuffer::new(3);
+    for i in 1..6 {
+        b.push(std::num::NonZeroU32::new(i).unwrap());
+    }
+    b.resize(4);
+    b.push(std::num::NonZeroU32::new(6).unwrap());
+    let v: Vec<u32> = b.map(|x| x.get()).collect();
+    assert_eq!(vec![3, 4, 5, 6], v);
+
+    let mut b: CircularBuffer<Option<Box<u32>>> = CircularBuffer::new(2);
+    b.push(Some(Box::new(1)));
+    b.push(None);
+    b.push(Some(Box::new(3)));
+    let v: Vec<_> = b.collect();
+    assert_eq!(vec![None, Some(Box::new(3))], v);
+}
+
+static EMPTY_STATIC: StaticCircularBuffer<u32, 4> = StaticCircularBuffer::new();
+
+proptest! {
+    #[test]
+    fn static_buffer_behaves_like_the_heap_one(
+        matrix in proptest::collection::vec(
+            (proptest::collection::vec(0..1000u32, 0..20), 0..20usize),
+            0..50)
+        ) {
+        let mut a = CircularBuffer::<u32>::new(7);
+        let mut b = StaticCircularBuffer::<u32, 7>::new();
+        for (to_add, to_remove) in matrix {
+            for i in to_add {
+                assert_eq!(a.push(i), b.push(i));
+            }
+            assert!(a.iter().eq(b.iter()));
+            let mut a_drainer = Vec::with_capacity(to_remove);
+            let mut b_drainer = Vec::with_capacity(to_remove);
+            assert_eq!(a.fill(&mut a_drainer), b.fill(&mut b_drainer));
+            assert_eq!(a_drainer, b_drainer);
+            assert_eq!(a.len(), b.len());
+        }
+    }
+}
+
+#[test]
+fn static_buffer_drops_its_elements() {
+    assert!(EMPTY_STATIC.is_empty());
+    assert_eq!(4, EMPTY_STATIC.capacity());
+
+    let counter = std::rc::Rc::new(());
+    let mut b = StaticCircularBuffer::<_, 3>::new();
+    for _ in 0..5 {
+        b.push(counter.clone());
+    }
+    assert_eq!(4, std::rc::Rc::strong_count(&counter));
+    b.next();
+    assert_eq!(3, std::rc::Rc::strong_count(&counter));
+    drop(b);
+    assert_eq!(1, std::rc::Rc::strong_count(&counter));
+
+    let mut empty = StaticCircularBuffer::<_, 0>::new();
+    assert_eq!(0, empty.push(counter.clone()));
+    assert_eq!(0, empty.len());
+    assert_eq!(1, std::rc::Rc::strong_count(&counter));
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn fill_heapless_stops_when_the_vector_is_full() {
+    let mut b = StaticCircularBuffer::<u32, 4>::new();
+    for i in 0..6 {
+        b.push(i);
+    }
+    let mut v: heapless::Vec<u32, 3> = heapless::Vec::new();
+    v.push(10).unwrap();
+    assert_eq!(2, b.fill_heapless(&mut v));
+    assert_eq!(&[10, 2, 3], v.as_slice());
+    assert_eq!(0, b.fill_heapless(&mut v));
+    assert_eq!(2, b.len());
+
+    let mut v: heapless::Vec<u32, 8> = heapless::Vec::new();
+    assert_eq!(2, b.fill_heapless(&mut v));
+    assert_eq!(&[4, 5], v.as_slice());
+}
+
+proptest! {
+    #[test]
+    fn borrowed_buffer_behaves_like_the_heap_one(
+        size in 0..10usize,
+        matrix in proptest::collection::vec(
+            (proptest::collection::vec(0..1000u32, 0..20), 0..20usize),
+            0..50)
+        ) {
+        let mut storage = vec![std::mem::MaybeUninit::uninit(); size];
+        let mut a = CircularBuffer::<u32>::new(size);
+        let mut b = BorrowedCircularBuffer::from_storage(&mut storage);
+        for (to_add, to_remove) in matrix {
+            for i in to_add {
+                assert_eq!(a.push(i), b.push(i));
+            }
+            assert!(a.iter().eq(b.iter()));
+            let mut a_drainer = Vec::with_capacity(to_remove);
+            let mut b_drainer = Vec::with_capacity(to_remove);
+            assert_eq!(a.fill(&mut a_drainer), b.fill(&mut b_drainer));
+            assert_eq!(a_drainer, b_drainer);
+            assert_eq!(a.len(), b.len());
+        }
+    }
+}
+
+#[test]
+fn borrowed_buffer_drops_its_elements_not_the_storage() {
+    let counter = std::rc::Rc::new(());
+    let mut storage: [std::mem::MaybeUninit<std::rc::Rc<()>>; 3] =
+        [const { std::mem::MaybeUninit::uninit() }; 3];
+    {
+        let mut b = BorrowedCircularBuffer::from_storage(&mut storage);
+        for _ in 0..5 {
+            b.push(counter.clone());
+        }
+        assert_eq!(4, std::rc::Rc::strong_count(&counter));
+    }
+    assert_eq!(1, std::rc::Rc::strong_count(&counter));
+
+    // the storage can be used again once the first buffer is gone
+    let mut b = BorrowedCircularBuffer::from_storage(&mut storage);
+    b.push(counter.clone());
+    assert_eq!(1, b.len());
+    assert_eq!(2, std::rc::Rc::strong_count(&counter));
+}
+
+#[test]
+fn self_test_passes_on_any_capacity() {
+    for capacity in [0, 1, 2, 3, 7, 64] {
+        let mut storage = vec![std::mem::MaybeUninit::uninit(); capacity];
+        let checks = BorrowedCircularBuffer::self_test(&mut storage).unwrap();
+        assert!(checks > capacity * 4);
+    }
+    let e = SelfTestError::new(3, 7, None);
+    assert_eq!("self test failed at check 3: expected 0x00000007, found nothing", e.to_string());
+}
+
+#[test]
+fn iter_rev_walks_a_wrapped_buffer_from_the_newest() {
+    let mut b = CircularBuffer::new(4);
+    b.extend_from_slice(&[1, 2, 3, 4, 5, 6]);
+    assert!(b.iter().rev().eq(b.iter_rev()));
+    let mut it = b.iter_rev();
+    assert_eq!(Some(&6), it.next());
+    assert_eq!(Some(&3), it.next_back());
+    assert_eq!(2, it.len());
+    let aged: Vec<_> = b.iter_rev().enumerate_from_newest().collect();
+    assert_eq!(vec![(0, &6), (1, &5), (2, &4), (3, &3)], aged);
+    assert_eq!(4, b.len());
+
+    let mut s = StaticCircularBuffer::<u32, 3>::new();
+    for i in 0..5 {
+        s.push(i);
+    }
+    assert!(s.iter_rev().eq([4, 3, 2].iter()));
+}
+
+#[test]
+fn display_last_formats_the_newest_elements() {
+    let mut b = CircularBuffer::new(4);
+    assert_eq!("CircularBuffer(<empty>)", format!("{}", b.display_last(2)));
+    b.extend_from_slice(&[1, 2, 3, 4, 5, 6]);
+    assert_eq!("CircularBuffer(.., 5, 6)", format!("{}", b.display_last(2)));
+    assert_eq!("CircularBuffer(..)", format!("{}", b.display_last(0)));
+    assert_eq!(format!("{}", b), format!("{}", b.display_last(4)));
+    assert_eq!(format!("{}", b), format!("{}", b.display_last(100)));
+    assert_eq!("CircularBuffer(.., 6)", format!("{:?}", b.debug_last(1)));
+}
+
+struct PanicOnDrop {
+    drops: std::rc::Rc<std::cell::Cell<usize>>,
+    panic: bool,
+}
+
+impl Drop for PanicOnDrop {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+        if self.panic {
+            panic!("drop failed");
+        }
+    }
+}
+
+// the first element panics when it is overwritten, every element must be dropped exactly once
+macro_rules! overwriting_a_panicking_drop {
+    ($b:expr) => {{
+        let mut b = $b;
+        let drops = std::rc::Rc::new(std::cell::Cell::new(0));
+        let element = |panic| PanicOnDrop {
+            drops: drops.clone(),
+            panic,
+        };
+        b.push(element(true));
+        b.push(element(false));
+        let result =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| b.push(element(false))));
+        assert!(result.is_err());
+        assert_eq!(1, drops.get());
+        assert_eq!(2, b.len());
+        drop(b);
+        assert_eq!(3, drops.get());
+    }};
+}
+
+#[test]
+fn ring_push_survives_a_panicking_drop() {
+    overwriting_a_panicking_drop!(StaticCircularBuffer::<_, 2>::new());
+    let mut storage: [std::mem::MaybeUninit<PanicOnDrop>; 2] =
+        [const { std::mem::MaybeUninit::uninit() }; 2];
+    overwriting_a_panicking_drop!(BorrowedCircularBuffer::from_storage(&mut storage));
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn mmap_buffer_survives_reopening() {
+    let path = std::env::temp_dir().join(format!("rbl_circular_buffer_{}.ring", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    {
+        let mut b = MmapCircularBuffer::<[u16; 2]>::open_mmap(&path, 3).unwrap();
+        for i in 0..5 {
+            b.push([i, i * 10]);
+        }
+        assert_eq!(Some([2, 20]), b.next());
+        b.flush().unwrap();
+    }
+    let mut b = MmapCircularBuffer::<[u16; 2]>::open_mmap(&path, 3).unwrap();
+    assert!(b.iter().eq([[3, 30], [4, 40]].iter()));
+    b.push([5, 50]);
+    b.push([6, 60]);
+    assert!(b.iter().eq([[4, 40], [5, 50], [6, 60]].iter()));
+    drop(b);
+
+    let error = MmapCircularBuffer::<[u16; 2]>::open_mmap(&path, 4).err().unwrap();
+    assert_eq!(std::io::ErrorKind::InvalidData, error.kind());
+    let error = MmapCircularBuffer::<u64>::open_mmap(&path, 3).err().unwrap();
+    assert_eq!(std::io::ErrorKind::InvalidData, error.kind());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn split_moves_the_elements_between_threads() {
+    let mut b = CircularBuffer::new(4);
+    b.extend(["a", "b", "c", "d", "e"].iter().map(|s| s.to_string()));
+    let (mut producer, mut consumer) = b.split();
+    assert_eq!(4, consumer.len());
+    assert_eq!(Err("f".to_string()), producer.push("f".to_string()));
+    assert_eq!(Some("b".to_string()), consumer.pop());
+    assert_eq!(Ok(0), producer.push("f".to_string()));
+
+    let handle = std::thread::spawn(move || {
+        for i in 0..10_000 {
+            let mut value = i.to_string();
+            while let Err(v) = producer.push(value) {
+                value = v;
+                std::thread::yield_now();
+            }
+        }
+        producer
+    });
+    let mut received = Vec::new();
+    while received.len() < 4 + 10_000 {
+        match consumer.pop() {
+            Some(s) => received.push(s),
+            None => std::thread::yield_now(),
+        }
+    }
+    let producer = handle.join().unwrap();
+    assert!(producer.is_empty());
+    assert_eq!(vec!["c", "d", "e", "f", "0", "1"], received[..6].to_vec());
+    assert_eq!("9999", received.last().unwrap());
+
+    // the elements left are dropped with the last half
+    let counter = std::rc::Rc::new(());
+    let mut b = CircularBuffer::new(2);
+    b.push(counter.clone());
+    let (_, consumer) = b.split();
+    assert_eq!(2, std::rc::Rc::strong_count(&counter));
+    drop(consumer);
+    assert_eq!(1, std::rc::Rc::strong_count(&counter));
+}
+
+#[test]
+fn split_mut_halves_in_different_structs() {
+    struct Source<'a>(Writer<'a, u32>);
+    struct Sink<'a>(Reader<'a, u32>);
+
+    let mut b = CircularBuffer::growable(2);
+    b.push(0);
+    {
+        let (writer, reader) = b.split_mut();
+        let (mut source, mut sink) = (Source(writer), Sink(reader));
+        assert_eq!(Ok(0), source.0.push(1));
+        assert_eq!(Err(2), source.0.push(2));
+        assert_eq!(Some(&0), sink.0.peek());
+        assert_eq!(Some(0), sink.0.pop());
+        assert_eq!(1, source.0.free_space());
+        assert_eq!(Ok(0), source.0.push(2));
+        assert_eq!(2, sink.0.len());
+    }
+    assert_eq!(2, b.capacity());
+    assert_eq!("CircularBuffer(1, 2)", format!("{}", b));
+}
+
+#[test]
+fn buffers_move_between_threads() {
+    fn send<T: Send>(_: &T) {}
+    fn sync<T: Sync>(_: &T) {}
+
+    let mut b = CircularBuffer::with_policy(3, Priority);
+    b.extend([1, 2, 3, 4]);
+    send(&b);
+    sync(&b);
+    send(&b.iter());
+    sync(&b.iter_pairs());
+    send(&b.drain(..1));
+
+    let shared = std::sync::Arc::new(std::sync::Mutex::new(b));
+    let handles: Vec<_> = (10..14)
+        .map(|i| {
+            let shared = shared.clone();
+            std::thread::spawn(move || {
+                shared.lock().unwrap().push(i);
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    let b = std::thread::spawn(move || {
+        std::sync::Arc::try_unwrap(shared).ok().unwrap().into_inner().unwrap()
+    })
+    .join()
+    .unwrap();
+    let mut v: Vec<_> = b.collect();
+    v.sort();
+    assert_eq!(vec![11, 12, 13], v);
+}
+
+#[test]
+fn blocking_buffer_waits_for_elements_and_space() {
+    use std::time::Duration;
+
+    let queue = std::sync::Arc::new(BlockingCircularBuffer::new(2));
+    assert_eq!(None, queue.pop_timeout(Duration::from_millis(10)));
+    assert_eq!(1, queue.push(1));
+    assert_eq!(Some(1), queue.pop_timeout(Duration::from_millis(10)));
+
+    queue.push(2);
+    queue.push(3);
+    let consumer = {
+        let queue = queue.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            queue.pop_wait()
+        })
+    };
+    // returns only once the consumer popped
+    queue.wait_until_free(1);
+    assert_eq!(2, consumer.join().unwrap());
+    assert_eq!(1, queue.len());
+
+    let producer = {
+        let queue = queue.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            queue.push(4);
+        })
+    };
+    assert_eq!(3, queue.pop_wait());
+    assert_eq!(Some(4), queue.pop_timeout(Duration::from_secs(10)));
+    producer.join().unwrap();
+    assert!(queue.try_pop().is_none());
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_buffer_stream_waits_for_pushes() {
+    use futures_core::Stream;
+    use std::future::poll_fn;
+    use std::pin::Pin;
+
+    let samples = AsyncCircularBuffer::new(2);
+    let mut stream = samples.clone();
+    let producer = {
+        let samples = samples.clone();
+        std::thread::spawn(move || {
+            for i in 0..3 {
+                std::thread::sleep(std::time::Duration::from_millis(5));
+                samples.push(i);
+            }
+            samples.close();
+        })
+    };
+    let received = futures_executor::block_on(async {
+        let mut received = Vec::new();
+        while let Some(i) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+            received.push(i);
+        }
+        received
+    });
+    producer.join().unwrap();
+    assert_eq!(vec![0, 1, 2], received);
+    assert!(samples.is_empty());
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_buffer_sink_backpressure() {
+    use futures_core::Stream;
+    use futures_sink::Sink;
+    use std::future::poll_fn;
+    use std::pin::Pin;
+
+    // overwrite mode is always ready, the oldest elements are lost
+    let mut overwrite = AsyncCircularBuffer::new(2);
+    futures_executor::block_on(async {
+        for i in 0..4 {
+            poll_fn(|cx| Pin::new(&mut overwrite).poll_ready(cx)).await.unwrap();
+            Pin::new(&mut overwrite).start_send(i).unwrap();
+        }
+    });
+    assert_eq!(Some(2), overwrite.try_pop());
+
+    // without overwrite the sender waits for the stream to pop
+    let mut sink = AsyncCircularBuffer::no_overwrite(2);
+    let mut stream = sink.clone();
+    let sender = std::thread::spawn(move || {
+        futures_executor::block_on(async {
+            for i in 0..100 {
+                poll_fn(|cx| Pin::new(&mut sink).poll_ready(cx)).await.unwrap();
+                Pin::new(&mut sink).start_send(i).unwrap();
+            }
+            poll_fn(|cx| Pin::new(&mut sink).poll_close(cx)).await.unwrap();
+        })
+    });
+    let received = futures_executor::block_on(async {
+        let mut received = Vec::new();
+        while let Some(i) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+            received.push(i);
+        }
+        received
+    });
+    sender.join().unwrap();
+    assert_eq!((0..100).collect::<Vec<_>>(), received);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_buffer_sink_without_overwrite_holds_the_element_of_a_racing_clone() {
+    use futures_sink::Sink;
+    use std::pin::Pin;
+    use std::task::Context;
+
+    let mut cx = Context::from_waker(std::task::Waker::noop());
+    let mut first = AsyncCircularBuffer::no_overwrite(1);
+    let mut second = first.clone();
+    // both see the free space, the first send takes it
+    assert!(Pin::new(&mut first).poll_ready(&mut cx).is_ready());
+    assert!(Pin::new(&mut second).poll_ready(&mut cx).is_ready());
+    Pin::new(&mut first).start_send(1).unwrap();
+    Pin::new(&mut second).start_send(2).unwrap();
+    assert_eq!(1, first.len());
+
+    // the second element waits for room instead of overwriting the first
+    assert!(Pin::new(&mut second).poll_flush(&mut cx).is_pending());
+    assert!(Pin::new(&mut second).poll_ready(&mut cx).is_pending());
+    assert_eq!(Some(1), first.try_pop());
+    assert!(Pin::new(&mut second).poll_flush(&mut cx).is_ready());
+    assert_eq!(Some(2), first.try_pop());
+
+    // closing pushes the element held before closing the buffer
+    assert!(Pin::new(&mut first).poll_ready(&mut cx).is_ready());
+    assert!(Pin::new(&mut second).poll_ready(&mut cx).is_ready());
+    Pin::new(&mut first).start_send(3).unwrap();
+    Pin::new(&mut second).start_send(4).unwrap();
+    assert!(Pin::new(&mut second).poll_close(&mut cx).is_pending());
+    assert_eq!(Some(3), first.try_pop());
+    assert!(Pin::new(&mut second).poll_close(&mut cx).is_ready());
+    assert_eq!(Some(4), first.try_pop());
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_pop_waits_for_a_push() {
+    let queue = AsyncCircularBuffer::new(2);
+    queue.push(1);
+    let producer = {
+        let queue = queue.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            queue.close();
+            queue.push(2);
+        })
+    };
+    let popped = futures_executor::block_on(async { (queue.pop().await, queue.pop().await) });
+    producer.join().unwrap();
+    assert_eq!((1, 2), popped);
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn tokio_read_and_write_across_the_wrap() {
+    use std::future::poll_fn;
+    use std::pin::Pin;
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    let mut b = CircularBuffer::new(6);
+    let mut out = [0u8; 4];
+    let read = futures_executor::block_on(async {
+        let n = poll_fn(|cx| Pin::new(&mut b).poll_write(cx, b"hello")).await.unwrap();
+        assert_eq!(5, n);
+        let mut buf = ReadBuf::new(&mut out[..3]);
+        poll_fn(|cx| Pin::new(&mut b).poll_read(cx, &mut buf)).await.unwrap();
+        assert_eq!(b"hel", buf.filled());
+        poll_fn(|cx| Pin::new(&mut b).poll_write(cx, b" world")).await.unwrap();
+        let mut buf = ReadBuf::new(&mut out);
+        poll_fn(|cx| Pin::new(&mut b).poll_read(cx, &mut buf)).await.unwrap();
+        buf.filled().len()
+    });
+    // the buffer overwrote "lo", the oldest bytes
+    assert_eq!(b" wor", &out[..read]);
+    assert_eq!(2, b.len());
+}
+
+#[test]
+fn io_read_and_write_follow_the_policy() {
+    use std::io::{Read, Write};
+
+    let mut b = CircularBuffer::new(4);
+    write!(b, "{}-{}", 12, 345).unwrap();
+    let mut out = [0u8; 3];
+    assert_eq!(3, b.read(&mut out).unwrap());
+    assert_eq!(b"-34", &out);
+    assert_eq!(1, b.read(&mut out).unwrap());
+    assert_eq!(0, b.read(&mut out).unwrap());
+
+    let mut r = CircularBuffer::with_policy(4, Reject);
+    assert_eq!(6, r.write(b"abcdef").unwrap());
+    let mut v = Vec::new();
+    r.read_to_end(&mut v).unwrap();
+    assert_eq!(b"abcd", &v[..]);
+}
+
+#[test]
+fn buf_read_lines_across_the_wrap() {
+    use std::io::BufRead;
+
+    let mut b = CircularBuffer::new(8);
+    b.extend_from_slice(b"xxxxxab\n");
+    b.advance_read(5);
+    b.extend_from_slice(b"cd\ne");
+    // "ab\n" is at the end of the array, "cd\ne" at its start
+    assert_eq!(b"ab\n", b.fill_buf().unwrap());
+    let mut line = String::new();
+    b.read_line(&mut line).unwrap();
+    assert_eq!("ab\n", line);
+    line.clear();
+    b.read_line(&mut line).unwrap();
+    assert_eq!("cd\n", line);
+    b.consume(1);
+    assert!(b.fill_buf().unwrap().is_empty());
+}
+
+#[test]
+fn vectored_io_uses_both_halves() {
+    use std::io::{IoSlice, IoSliceMut, Read, Write};
+
+    let mut b = CircularBuffer::new(6);
+    let n = b.write_vectored(&[IoSlice::new(b"abcd"), IoSlice::new(b"efgh")]).unwrap();
+    assert_eq!(8, n);
+    let [older, newer] = b.as_io_slices();
+    assert_eq!((&b"cdef"[..], &b"gh"[..]), (&*older, &*newer));
+
+    let (mut first, mut second) = ([0u8; 3], [0u8; 5]);
+    let n = b
+        .read_vectored(&mut [IoSliceMut::new(&mut first), IoSliceMut::new(&mut second)])
+        .unwrap();
+    assert_eq!(6, n);
+    assert_eq!(b"cde", &first);
+    assert_eq!(b"fgh", &second[..3]);
+    assert!(b.as_io_slices().iter().all(|s| s.is_empty()));
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn bytes_buf_and_buf_mut_across_the_wrap() {
+    use bytes::{Buf, BufMut};
+
+    let mut b = CircularBuffer::new(6);
+    b.put_slice(b"abcd");
+    assert_eq!(b'a', b.get_u8());
+    assert_eq!(b'b', b.get_u8());
+    // "ef" fills the end of the array, "gh" wraps to the start
+    b.put_slice(b"efgh");
+    assert_eq!(0, b.remaining_mut());
+    assert_eq!(b"cdef", b.chunk());
+    let mut out = [0u8; 6];
+    Buf::copy_to_slice(&mut b, &mut out);
+    assert_eq!(b"cdefgh", &out);
+    assert_eq!(0, b.remaining());
+    assert_eq!(6, b.remaining_mut());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip_keeps_capacity_and_order() {
+    let mut b = CircularBuffer::new(4);
+    for word in ["a", "b", "c", "d", "e"] {
+        b.push(word.to_string());
+    }
+    b.next();
+    let json = serde_json::to_string(&b).unwrap();
+    assert_eq!(r#"[4,["c","d","e"]]"#, json);
+    let mut back: CircularBuffer<String> = serde_json::from_str(&json).unwrap();
+    assert_eq!(4, back.capacity());
+    assert_eq!(0, back.push("f".to_string()));
+    assert_eq!("CircularBuffer(c, d, e, f)", format!("{}", back));
+
+    assert!(serde_json::from_str::<CircularBuffer<u32>>("[1,[1,2]]").is_err());
+    assert!(serde_json::from_str::<CircularBuffer<u32>>("[1]").is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_does_not_allocate_an_untrusted_capacity_up_front() {
+    let mut back: CircularBuffer<u64> = serde_json::from_str("[18446744073709551615,[1]]").unwrap();
+    assert!(!back.is_full());
+    for i in 2..=100 {
+        assert_eq!(usize::MAX - i as usize, back.push(i));
+    }
+    assert_eq!(100, back.len());
+    assert_eq!(Some(1), back.next());
+
+    let mut small: CircularBuffer<u64> = serde_json::from_str("[3,[1]]").unwrap();
+    assert_eq!(3, small.capacity());
+    small.extend(2..=4);
+    assert_eq!("CircularBuffer(2, 3, 4)", format!("{}", small));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip_keeps_a_large_capacity_allocated_lazily() {
+    let mut b = CircularBuffer::new(1 << 20);
+    b.extend_from_slice(&[1u64, 2, 3]);
+    let json = serde_json::to_string(&b).unwrap();
+    let mut back: CircularBuffer<u64> = serde_json::from_str(&json).unwrap();
+    assert_eq!(json, serde_json::to_string(&back).unwrap());
+    assert_eq!(1 << 20, back.capacity());
+    assert_eq!((1 << 20) - 3, back.free_space());
+    assert!(back.memory_usage() < b.memory_usage());
+    let (copy, _) = back.clone().partition(|_| true);
+    assert_eq!(json, serde_json::to_string(&copy).unwrap());
+    let mut shrunk = back.clone();
+    shrunk.shrink_to_fit();
+    assert_eq!(3, shrunk.capacity());
+    assert!(shrunk.is_full());
+
+    // it grows up to the capacity, then it overwrites like the original
+    back.extend(4..=1 << 20);
+    assert!(back.is_full());
+    assert_eq!(0, back.push(0));
+    assert_eq!(Some(2), back.next());
+    let back: CircularBuffer<u64> = serde_json::from_str(&serde_json::to_string(&back).unwrap()).unwrap();
+    assert_eq!(1 << 20, back.capacity());
+}
+
+#[cfg(feature = "rkyv")]
+#[test]
+fn rkyv_archive_reads_the_elements_in_place() {
+    use rkyv::rancor::Error;
+
+    let mut b = CircularBuffer::new(4);
+    for word in ["a", "b", "c", "d", "e"] {
+        b.push(word.to_string());
+    }
+    b.next();
+    let bytes = rkyv::to_bytes::<Error>(&b).unwrap();
+    let archived =
+        rkyv::access::<ArchivedCircularBuffer<rkyv::Archived<String>>, Error>(&bytes).unwrap();
+    assert_eq!(4, archived.capacity());
+    assert_eq!(3, archived.len());
+    let words: Vec<&str> = archived.iter().map(|w| w.as_str()).collect();
+    assert_eq!(vec!["c", "d", "e"], words);
+
+    let mut back: CircularBuffer<String> = rkyv::deserialize::<_, Error>(archived).unwrap();
+    assert_eq!(0, back.push("f".to_string()));
+    assert_eq!("CircularBuffer(c, d, e, f)", format!("{}", back));
+
+    let empty: CircularBuffer<u64> = CircularBuffer::new(0);
+    let bytes = rkyv::to_bytes::<Error>(&empty).unwrap();
+    let archived = rkyv::access::<ArchivedCircularBuffer<rkyv::Archived<u64>>, Error>(&bytes);
+    assert!(archived.unwrap().is_empty());
+}
+
+#[cfg(feature = "rkyv")]
+#[test]
+fn rkyv_round_trip_keeps_a_large_capacity_allocated_lazily() {
+    use rkyv::rancor::Error;
+
+    let mut b = CircularBuffer::new(10_000_000);
+    b.extend_from_slice(&[1u8, 2, 3]);
+    let bytes = rkyv::to_bytes::<Error>(&b).unwrap();
+    let archived = rkyv::access::<ArchivedCircularBuffer<u8>, Error>(&bytes).unwrap();
+    let back: CircularBuffer<u8> = rkyv::deserialize::<_, Error>(archived).unwrap();
+    assert_eq!(10_000_000, back.capacity());
+    assert!(!back.is_full());
+    assert!(back.memory_usage() < b.memory_usage());
+
+    let again = rkyv::to_bytes::<Error>(&back).unwrap();
+    assert_eq!(&bytes[..], &again[..]);
+}
+
+#[test]
+fn equality_ignores_where_the_elements_are_stored() {
+    let mut a = CircularBuffer::new(3);
+    for i in 1..=5 {
+        a.push(i);
+    }
+    let b = CircularBuffer::from(vec![3, 4, 5]);
+    assert_ne!(a.as_raw_slices(), b.as_raw_slices());
+    assert_eq!(a, b);
+
+    let mut c = CircularBuffer::new(3);
+    c.extend_from_slice(&[3, 4]);
+    assert_ne!(a, c);
+    c.push(6);
+    assert_ne!(a, c);
+
+    let empty: CircularBuffer<u32> = CircularBuffer::new(0);
+    a.clear();
+    assert_eq!(empty, a);
+}
+
+#[test]
+fn equality_with_slices_arrays_and_vecs() {
+    let mut b = CircularBuffer::new(3);
+    for i in 1..=4 {
+        b.push(i);
+    }
+    assert_eq!(b, [2, 3, 4]);
+    assert_eq!(b, &[2, 3, 4]);
+    assert_eq!(b, vec![2, 3, 4]);
+    assert_eq!(b, &[2, 3, 4][..]);
+    assert_eq!(b, *vec![2, 3, 4].as_slice());
+    assert_ne!(b, [2, 3]);
+    assert_ne!(b, [2, 3, 4, 5]);
+    assert_ne!(b, [1, 3, 4]);
+    assert_ne!(b, [2, 3, 5]);
+
+    b.clear();
+    assert_eq!(b, [0; 0]);
+    assert_eq!(b, Vec::<i32>::new());
+}
+
+#[test]
+fn equal_buffers_have_the_same_hash() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let mut a = CircularBuffer::new(3);
+    for i in 1..=5 {
+        a.push(i);
+    }
+    let b = CircularBuffer::from(vec![3, 4, 5]);
+    assert_eq!(hash_of(&a), hash_of(&b));
+    a.push(6);
+    assert_ne!(hash_of(&a), hash_of(&b));
+
+    // the length keeps the elements of nested buffers apart
+    let nested_a = circular_buffer![circular_buffer![1, 2], circular_buffer![]];
+    let nested_b = circular_buffer![circular_buffer![1], circular_buffer![2]];
+    assert_ne!(hash_of(&nested_a), hash_of(&nested_b));
+}
+
+#[test]
+fn buffers_are_ordered_like_slices() {
+    let mut a = CircularBuffer::new(3);
+    for i in 1..=5 {
+        a.push(i);
+    }
+    let mut sorted = [
+        circular_buffer![3, 4, 6],
+        a.clone(),
+        circular_buffer![3, 4],
+        circular_buffer![],
+        circular_buffer![2, 9, 9],
+    ];
+    sorted.sort();
+    let slices: Vec<Vec<i32>> = sorted.iter().map(|b| b.iter().copied().collect()).collect();
+    assert_eq!(vec![vec![], vec![2, 9, 9], vec![3, 4], vec![3, 4, 5], vec![3, 4, 6]], slices);
+    assert_eq!(std::cmp::Ordering::Equal, Ord::cmp(&a, &circular_buffer![3, 4, 5]));
+
+    let floats = circular_buffer![1.0, f64::NAN];
+    assert_eq!(None, PartialOrd::partial_cmp(&floats, &circular_buffer![1.0, 2.0]));
+    assert!(floats < circular_buffer![2.0]);
+}
+
+#[test]
+fn default_buffer_is_empty_until_resized() {
+    #[derive(Default)]
+    struct Window {
+        samples: CircularBuffer<u32>,
+        rejected: CircularBuffer<u32, Reject>,
+    }
+
+    let mut window = Window::default();
+    assert_eq!(0, window.samples.capacity());
+    assert_eq!(0, window.samples.push(1));
+    assert!(window.samples.is_empty());
+    assert_eq!(0, window.rejected.capacity());
+
+    window.samples.resize(2);
+    window.samples.extend_from_slice(&[1, 2, 3]);
+    assert_eq!(window.samples, [2, 3]);
+}
+
+#[test]
+fn as_slices_follow_the_wrap_around() {
+    let mut b = CircularBuffer::new(4);
+    assert_eq!((&[][..], &[][..]), b.as_slices());
+    for i in 0..6 {
+        b.push(i);
+    }
+    assert_eq!((&[2, 3][..], &[4, 5][..]), b.as_slices());
+    b.next();
+    b.next();
+    assert_eq!((&[4, 5][..], &[][..]), b.as_slices());
+    b.push(6);
+    b.push(7);
+    b.push(8);
+
+    let (older, newer) = b.as_mut_slices();
+    older.iter_mut().chain(newer.iter_mut()).for_each(|e| *e *= 10);
+    assert_eq!(b, [50, 60, 70, 80]);
+    let (older, newer) = b.as_slices();
+    assert_eq!(4, older.len() + newer.len());
+}
+
+#[test]
+fn with_slices_gives_the_two_halves_in_order() {
+    fn concat<T: Clone>(older: &[T], newer: &[T]) -> Vec<T> {
+        [older, newer].concat()
+    }
+
+    let mut b = CircularBuffer::new(3);
+    assert_eq!(Vec::<u8>::new(), b.with_slices(concat));
+    for i in 0..5u8 {
+        b.push(i);
+    }
+    assert_eq!((1, 2), b.with_slices(|older, newer| (older.len(), newer.len())));
+    assert_eq!(vec![2, 3, 4], b.with_slices(concat));
+}
+
+#[test]
+fn to_vec_clones_without_consuming() {
+    let mut b = CircularBuffer::new(3);
+    assert!(b.to_vec().is_empty());
+    for word in ["a", "b", "c", "d"] {
+        b.push(word.to_string());
+    }
+    let v = b.to_vec();
+    assert_eq!(vec!["b", "c", "d"], v);
+    assert_eq!(3, v.capacity());
+    assert_eq!(b, v);
+}
+
+#[test]
+fn into_vec_reuses_the_array() {
+    for popped in 0..4 {
+        let mut b = CircularBuffer::new(4);
+        for i in 0..6 {
+            b.push(i.to_string());
+        }
+        for _ in 0..popped {
+            b.next();
+        }
+        let expected = b.to_vec();
+        let array = b.as_mut_ptr() as usize;
+        let v = b.into_vec();
+        assert_eq!(expected, v);
+        assert_eq!(array, v.as_ptr() as usize);
+        assert_eq!(4, v.capacity());
+    }
+
+    assert!(CircularBuffer::<u32>::new(0).into_vec().is_empty());
+    let mut units = CircularBuffer::new(3);
+    units.extend([(), (), (), ()]);
+    assert_eq!(3, units.into_vec().len());
+}
+
+#[test]
+fn conversions_with_vec_deque_keep_the_order() {
+    use std::collections::VecDeque;
+
+    let mut deque = VecDeque::with_capacity(8);
+    deque.extend([3, 4, 5]);
+    deque.push_front(2);
+    deque.push_front(1);
+    let mut b = CircularBuffer::from(deque);
+    assert_eq!(5, b.capacity());
+    assert_eq!(b, [1, 2, 3, 4, 5]);
+
+    b.push(6);
+    b.next();
+    let deque = VecDeque::from(b);
+    assert_eq!(vec![3, 4, 5, 6], Vec::from(deque));
+
+    let empty: VecDeque<u32> = CircularBuffer::new(0).into();
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn fill_slice_moves_as_many_elements_as_fit() {
+    let mut b = CircularBuffer::new(3);
+    for word in ["a", "b", "c", "d"] {
+        b.push(word.to_string());
+    }
+    let mut out = vec![String::new(); 2];
+    assert_eq!(2, b.fill_slice(&mut out));
+    assert_eq!(vec!["b", "c"], out);
+    assert_eq!(b, ["d"]);
+    assert_eq!(1, b.fill_slice(&mut out));
+    assert_eq!(vec!["d", "c"], out);
+    assert_eq!(0, b.fill_slice(&mut out));
+    assert_eq!(0, b.fill_slice(&mut []));
+
+    let mut s = StaticCircularBuffer::<u32, 2>::new();
+    s.push(1);
+    s.push(2);
+    s.push(3);
+    let mut out = [0; 1];
+    assert_eq!(1, s.fill_slice(&mut out));
+    assert_eq!([2], out);
+    assert_eq!(1, s.len());
+
+    let mut storage = [std::mem::MaybeUninit::uninit(); 2];
+    let mut borrowed = BorrowedCircularBuffer::from_storage(&mut storage);
+    borrowed.push(4);
+    assert_eq!(1, borrowed.fill_slice(&mut out));
+    assert_eq!([4], out);
+    assert!(borrowed.is_empty());
+}
+
+#[test]
+fn make_contiguous_from_every_position() {
+    for size in 1..6 {
+        for pushed in 0..2 * size {
+            for popped in 0..=pushed.min(size) {
+                let mut b = CircularBuffer::new(size);
+                for i in 0..pushed {
+                    b.push(i.to_string());
+                }
+                for _ in 0..popped {
+                    b.next();
+                }
+                let expected: Vec<String> = b.iter().cloned().collect();
+                assert_eq!(expected, b.make_contiguous());
+                assert_eq!(b, expected);
+                assert!(b.as_slices().1.is_empty());
+
+                b.push("new".to_string());
+                let last = b.iter_rev().next().unwrap();
+                assert_eq!("new", last);
+            }
+        }
+    }
+}
+
+#[test]
+fn global_allocator_is_the_default() {
+    let mut b: CircularBuffer<u32, OldestFirst, Global> = CircularBuffer::new_in(2, Global);
+    b.extend_from_slice(&[1, 2, 3]);
+    b.resize(3);
+    let c = b.clone();
+    assert_eq!("CircularBuffer(2, 3)", format!("{}", c));
+    let _: &Global = c.allocator();
+}
+
+#[cfg(feature = "allocator_api")]
+#[test]
+fn custom_allocator_serves_every_allocation() {
+    use core::alloc::{AllocError, Allocator, Layout};
+    use core::ptr::NonNull;
+    use std::cell::Cell;
+
+    #[derive(Clone, Copy)]
+    struct Counting<'a>(&'a Cell<isize>);
+
+    unsafe impl<'a> Allocator for Counting<'a> {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.0.set(self.0.get() + 1);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.0.set(self.0.get() - 1);
+            Global.deallocate(ptr, layout)
+        }
+    }
+
+    let live = Cell::new(0);
+    {
+        let mut b = CircularBuffer::new_in(2, Counting(&live));
+        b.extend_from_slice(&[1, 2, 3]);
+        assert_eq!(1, live.get());
+        b.resize(4);
+        let c = b.clone();
+        assert_eq!(2, live.get());
+        assert_eq!("CircularBuffer(2, 3)", format!("{}", c));
+    }
+    assert_eq!(0, live.get());
+}
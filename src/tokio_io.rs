@@ -0,0 +1,45 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::{Allocator, CircularBuffer, EvictionPolicy};
+
+/// Read the oldest bytes, removing them from the CircularBuffer.
+///
+/// It never waits: an empty CircularBuffer reads 0 bytes, the end of the stream.
+impl<P: Unpin, A: Allocator + Unpin> AsyncRead for CircularBuffer<u8, P, A> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let n = self.get_mut().read_into(buf.initialize_unfilled());
+        buf.advance(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Push the bytes, with the same semantics of `CircularBuffer::extend_from_slice`.
+///
+/// It never waits and always accepts the whole slice: when the CircularBuffer is full its policy
+/// decides which bytes are lost.
+impl<P: EvictionPolicy<u8> + Unpin, A: Allocator + Unpin> AsyncWrite for CircularBuffer<u8, P, A> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}